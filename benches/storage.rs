@@ -0,0 +1,53 @@
+/// storage.rs
+/// - Benchmark of the cell storages of the life-game.
+///   Run with `cargo bench --bench storage`.
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use rust_lifegame::model::{LifeGame, Storage};
+
+/// Fill the board with a reproducible pseudo random soup.
+fn fill(life_game: &mut LifeGame, seed: u64) {
+    let mut state = seed;
+    for y in 0..life_game.get_height() as i32 {
+        for x in 0..life_game.get_width() as i32 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            if state.is_multiple_of(3) {
+                life_game.set_alive(x, y, true);
+            }
+        }
+    }
+}
+
+/// Measure the average time of a generation.
+fn bench(size: usize, storage: Storage, threads: usize, generations: u32) -> Duration {
+    let mut life_game = LifeGame::with_storage(size, size, storage);
+    life_game.set_threads(threads);
+    fill(&mut life_game, 0x2545_f491_4f6c_dd1d);
+    let start = Instant::now();
+    for _ in 0..generations {
+        life_game.next_generation();
+        black_box(&life_game);
+    }
+    start.elapsed() / generations
+}
+
+fn main() {
+    println!("{:>6} {:>14} {:>14} {:>8} {:>14}", "size", "cells", "bit-packed", "speedup", "all threads");
+    for size in [64, 256, 1024] {
+        let generations = (1 << 22) / (size * size) as u32 + 1;
+        let cells = bench(size, Storage::Cells, 1, generations);
+        let bits = bench(size, Storage::BitPacked, 1, generations);
+        let parallel = bench(size, Storage::BitPacked, 0, generations);
+        println!(
+            "{:>6} {:>14?} {:>14?} {:>7.1}x {:>14?}",
+            size,
+            cells,
+            bits,
+            cells.as_secs_f64() / bits.as_secs_f64(),
+            parallel
+        );
+    }
+}
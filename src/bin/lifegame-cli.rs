@@ -0,0 +1,389 @@
+/// lifegame-cli.rs
+/// - Headless runner of the life game, without the GTK dependency.
+use std::fs;
+use std::io::{self, Write};
+use std::process::ExitCode;
+
+use rust_lifegame::format::{self, Format, PatternData};
+use rust_lifegame::model::{
+    BitGrid, Cycle, CycleDetector, Engine, GenerationStats, HashLife, LifeGame, Rule, SparseLife, Storage, Topology,
+    Universe, CSV_HEADER, MAX_BOARD_SIZE,
+};
+
+const USAGE: &str = "\
+Usage: lifegame-cli [OPTIONS] <PATTERN>
+
+Run the pattern file and write the result to stdout.
+
+Options:
+  -r, --rule <RULE>          Rule such as B3/S23 [default: rule of the pattern, or B3/S23]
+  -t, --topology <TOPOLOGY>  torus, plane, cylinder, klein-bottle or cross-surface [default: torus]
+  -s, --size <WxH>           Size of the board, grown to fit the pattern [default: 64x64]
+  -e, --engine <ENGINE>      grid, or sparse or hashlife on the unbounded plane [default: grid]
+  -n, --generations <N>      Number of generations to run [default: 1000]
+  -u, --until-stable         Stop early when the pattern dies out or becomes periodic
+  -o, --output <OUTPUT>      Final pattern in rle, cells, life105, life106 or mc,
+                             or per-generation stats in csv or json [default: rle]
+  -h, --help                 Print this help";
+
+/// What is written to stdout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Output {
+    /// The final pattern in the format.
+    Pattern(Format),
+    /// The stats of every generation as comma separated values.
+    Csv,
+    /// The stats of every generation as a JSON object per line.
+    Json,
+}
+
+impl Output {
+    /// Parse the output name given on the command line.
+    fn parse(name: &str) -> Option<Output> {
+        match name.to_lowercase().as_str() {
+            "rle" => Some(Output::Pattern(Format::Rle)),
+            "cells" | "plaintext" => Some(Output::Pattern(Format::Plaintext)),
+            "life105" => Some(Output::Pattern(Format::Life105)),
+            "life106" | "lif" => Some(Output::Pattern(Format::Life106)),
+            "mc" | "macrocell" => Some(Output::Pattern(Format::Macrocell)),
+            "csv" => Some(Output::Csv),
+            "json" => Some(Output::Json),
+            _ => None,
+        }
+    }
+    /// Check if the stats of every generation are written.
+    fn is_stats(&self) -> bool {
+        matches!(self, Output::Csv | Output::Json)
+    }
+}
+
+/// Options given on the command line.
+#[derive(Debug, Clone)]
+struct Options {
+    path: String,
+    rule: Option<Rule>,
+    topology: Topology,
+    width: usize,
+    height: usize,
+    engine: Engine,
+    generations: u64,
+    until_stable: bool,
+    output: Output,
+}
+
+/// Parse the board size such as `64x48`, up to the largest board.
+fn parse_size(size: &str) -> Option<(usize, usize)> {
+    let (width, height) = size.split_once(['x', 'X'])?;
+    let valid = |size: usize| (1..=MAX_BOARD_SIZE).contains(&size);
+    match (width.parse(), height.parse()) {
+        (Ok(width), Ok(height)) if valid(width) && valid(height) => Some((width, height)),
+        _ => None,
+    }
+}
+
+/// Parse the command line arguments.
+/// Returns `Ok(None)` if the help is requested.
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Option<Options>, String> {
+    let mut path = None;
+    let mut options = Options {
+        path: String::new(),
+        rule: None,
+        topology: Topology::default(),
+        width: 64,
+        height: 64,
+        engine: Engine::default(),
+        generations: 1000,
+        until_stable: false,
+        output: Output::Pattern(Format::Rle),
+    };
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("missing value of {}", arg));
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-r" | "--rule" => {
+                let rule = value()?;
+                options.rule = Some(rule.parse().map_err(|error| format!("invalid rule {}: {}", rule, error))?);
+            }
+            "-t" | "--topology" => {
+                let topology = value()?;
+                options.topology = Topology::from_name(&topology).ok_or(format!("unknown topology {}", topology))?;
+            }
+            "-s" | "--size" => {
+                let size = value()?;
+                (options.width, options.height) = parse_size(&size).ok_or(format!("invalid size {}", size))?;
+            }
+            "-e" | "--engine" => {
+                let engine = value()?;
+                options.engine = Engine::from_name(&engine).ok_or(format!("unknown engine {}", engine))?;
+            }
+            "-n" | "--generations" => {
+                let generations = value()?;
+                options.generations =
+                    generations.parse().map_err(|_| format!("invalid number of generations {}", generations))?;
+            }
+            "-u" | "--until-stable" => options.until_stable = true,
+            "-o" | "--output" => {
+                let output = value()?;
+                options.output = Output::parse(&output).ok_or(format!("unknown output {}", output))?;
+            }
+            _ if arg.starts_with('-') => return Err(format!("unknown option {}", arg)),
+            _ if path.is_none() => path = Some(arg),
+            _ => return Err(format!("unexpected argument {}", arg)),
+        }
+    }
+    options.path = path.ok_or("missing pattern file")?;
+    if options.engine != Engine::Grid {
+        let name = options.engine.name().to_lowercase();
+        if !options.engine.supports(options.topology) {
+            return Err(format!("the {} engine runs on the plane only, use --topology plane", name));
+        }
+        if options.output.is_stats() {
+            return Err(format!("the {} engine does not write the stats, use the grid engine", name));
+        }
+    }
+    Ok(Some(options))
+}
+
+/// Write the stats of the generation.
+fn write_stats<W: Write>(out: &mut W, output: Output, stats: &GenerationStats) -> io::Result<()> {
+    match output {
+        Output::Csv => writeln!(out, "{}", stats.to_csv()),
+        Output::Json => {
+            let bounds = stats.get_bounding_box().map_or("null".to_string(), |bounds| {
+                let ((left, top), (right, bottom)) = (bounds.get_min(), bounds.get_max());
+                format!("{{\"left\":{},\"top\":{},\"right\":{},\"bottom\":{}}}", left, top, right, bottom)
+            });
+            writeln!(
+                out,
+                "{{\"generation\":{},\"span\":{},\"population\":{},\"births\":{},\"deaths\":{},\"bounding_box\":{},\
+                 \"density\":{},\"rate_of_change\":{}}}",
+                stats.get_generation(),
+                stats.get_span(),
+                stats.get_population(),
+                stats.get_births(),
+                stats.get_deaths(),
+                bounds,
+                stats.get_density(),
+                stats.get_rate_of_change()
+            )
+        }
+        Output::Pattern(_) => Ok(()),
+    }
+}
+
+/// Why the run stopped before the last generation.
+enum Stop {
+    Extinct,
+    Cycle(Cycle),
+}
+
+/// Run the pattern with the options, writing the output to `out`.
+fn run<W: Write>(options: &Options, out: &mut W) -> Result<(), String> {
+    let text = fs::read_to_string(&options.path).map_err(|error| format!("{}: {}", options.path, error))?;
+    let pattern = format::parse(&text).map_err(|error| format!("{}: {}", options.path, error))?;
+    let rule = options.rule.or(pattern.get_rule().copied());
+    let rule_error = |error| format!("{}: {}", options.path, error);
+    match options.engine {
+        Engine::Grid => {
+            let mut life_game = LifeGame::with_storage(options.width, options.height, Storage::BitPacked);
+            life_game.set_topology(options.topology);
+            pattern.load_into(&mut life_game).map_err(|error| format!("{}: {}", options.path, error))?;
+            if let Some(rule) = options.rule {
+                life_game.set_rule(rule);
+            }
+            simulate(options, &pattern, &mut life_game, Some(LifeGame::to_bit_grid), out)
+        }
+        Engine::Sparse => {
+            let mut sparse_life = SparseLife::from_cells(pattern.get_cells().iter().copied());
+            sparse_life.set_generation(pattern.get_generation());
+            if let Some(rule) = rule {
+                sparse_life.set_rule(rule).map_err(rule_error)?;
+            }
+            simulate(options, &pattern, &mut sparse_life, None, out)
+        }
+        Engine::HashLife => {
+            let mut hash_life = HashLife::from_cells(pattern.get_cells().iter().copied());
+            hash_life.set_generation(pattern.get_generation());
+            if let Some(rule) = rule {
+                hash_life.set_rule(rule).map_err(rule_error)?;
+            }
+            simulate(options, &pattern, &mut hash_life, None, out)
+        }
+    }
+}
+
+/// Run the universe loaded with the pattern for the generations of the options, writing the output to `out`.
+/// The stats are written only if the board of the universe can be copied by `to_cells`.
+fn simulate<U: Universe, W: Write>(
+    options: &Options,
+    pattern: &PatternData,
+    universe: &mut U,
+    to_cells: Option<fn(&U) -> BitGrid>,
+    out: &mut W,
+) -> Result<(), String> {
+    let mut detector = CycleDetector::default();
+    let mut stop = None;
+    let write_error = |error: io::Error| error.to_string();
+    if options.output == Output::Csv {
+        writeln!(out, "{}", CSV_HEADER).map_err(write_error)?;
+    }
+    // The board is copied for the births and the deaths only if the stats are written.
+    let to_cells = to_cells.filter(|_| options.output.is_stats());
+    let mut cells = to_cells.map(|to_cells| to_cells(universe));
+    if let Some(cells) = &cells {
+        write_stats(out, options.output, &GenerationStats::of(cells, universe.get_generation())).map_err(write_error)?;
+    }
+    for _ in 0..options.generations {
+        let before = cells.take().map(|cells| (cells, universe.get_generation()));
+        universe.next_generation();
+        if let (Some(to_cells), Some((before, before_generation))) = (to_cells, before) {
+            let after = to_cells(universe);
+            let stats = GenerationStats::new(&before, before_generation, &after, universe.get_generation());
+            write_stats(out, options.output, &stats).map_err(write_error)?;
+            cells = Some(after);
+        }
+        if !options.until_stable {
+            continue;
+        }
+        if universe.population() == 0 {
+            stop = Some(Stop::Extinct);
+        } else if let Some(cycle) = detector.observe(universe) {
+            stop = Some(Stop::Cycle(cycle));
+        }
+        if stop.is_some() {
+            break;
+        }
+    }
+
+    if let Output::Pattern(format) = options.output {
+        let mut result = PatternData::from_universe(universe);
+        result.set_name(pattern.get_name().map(str::to_string));
+        result.add_comment(format!("Generation: {}", universe.get_generation()));
+        write!(out, "{}", format.write(&result)).map_err(write_error)?;
+    }
+    match stop {
+        Some(Stop::Extinct) => eprintln!("died out at generation {}", universe.get_generation()),
+        Some(Stop::Cycle(cycle)) => eprintln!("{} found at generation {}", cycle, universe.get_generation()),
+        None => {}
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(error) => {
+            eprintln!("error: {}\n\n{}", error, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    let stdout = io::stdout();
+    let mut out = io::BufWriter::new(stdout.lock());
+    if let Err(error) = run(&options, &mut out).and_then(|_| out.flush().map_err(|error| error.to_string())) {
+        eprintln!("error: {}", error);
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Option<Options>, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parses_size() {
+        assert_eq!(parse_size("64x48"), Some((64, 48)));
+        assert_eq!(parse_size("10X20"), Some((10, 20)));
+        assert_eq!(parse_size("0x5"), None);
+        assert_eq!(parse_size("64"), None);
+        assert_eq!(parse_size("ax5"), None);
+        assert_eq!(parse_size("-1x5"), None);
+        assert_eq!(parse_size("4096x4096"), Some((4096, 4096)));
+        assert_eq!(parse_size("4097x5"), None);
+    }
+
+    #[test]
+    fn parses_defaults() {
+        let options = parse(&["glider.rle"]).unwrap().unwrap();
+        assert_eq!(options.path, "glider.rle");
+        assert_eq!(options.rule, None);
+        assert_eq!(options.topology, Topology::Torus);
+        assert_eq!((options.width, options.height), (64, 64));
+        assert_eq!(options.engine, Engine::Grid);
+        assert_eq!(options.generations, 1000);
+        assert!(!options.until_stable);
+        assert_eq!(options.output, Output::Pattern(Format::Rle));
+    }
+
+    #[test]
+    fn parses_options() {
+        let args = ["-r", "B36/S23", "--topology", "klein-bottle", "-s", "32x16", "-n", "10", "-u", "-o", "csv"];
+        let options = parse(&[&args[..], &["gun.rle"]].concat()).unwrap().unwrap();
+        assert_eq!(options.path, "gun.rle");
+        assert_eq!(options.rule, Some("B36/S23".parse().unwrap()));
+        assert_eq!(options.topology, Topology::KleinBottle);
+        assert_eq!((options.width, options.height), (32, 16));
+        assert_eq!(options.generations, 10);
+        assert!(options.until_stable);
+        assert_eq!(options.output, Output::Csv);
+        assert_eq!(parse(&["-o", "mc", "a.mc"]).unwrap().unwrap().output, Output::Pattern(Format::Macrocell));
+    }
+
+    #[test]
+    fn parses_unbounded_engines_on_plane_only() {
+        let options = parse(&["-e", "sparse", "-t", "plane", "a.rle"]).unwrap().unwrap();
+        assert_eq!(options.engine, Engine::Sparse);
+        assert_eq!(parse(&["--engine", "HashLife", "-t", "plane", "a.rle"]).unwrap().unwrap().engine, Engine::HashLife);
+        assert!(parse(&["-e", "sparse", "a.rle"]).is_err());
+        assert!(parse(&["-e", "sparse", "-t", "plane", "-o", "csv", "a.rle"]).is_err());
+        assert!(parse(&["-e", "quadtree", "-t", "plane", "a.rle"]).is_err());
+    }
+
+    #[test]
+    fn sparse_engine_matches_grid_engine_on_plane() {
+        let path = std::env::temp_dir().join(format!("lifegame-cli-{}-r-pentomino.rle", std::process::id()));
+        fs::write(&path, "x = 3, y = 3, rule = B3/S23\nb2o$2ob$bo!\n").unwrap();
+        let output = |engine: &str| {
+            let args = ["-e", engine, "-t", "plane", "-s", "256x256", "-n", "100", path.to_str().unwrap()];
+            let options = parse(&args).unwrap().unwrap();
+            let mut out = Vec::new();
+            run(&options, &mut out).unwrap();
+            let text = String::from_utf8(out).unwrap();
+            let mut cells = format::parse(&text).unwrap().get_cells().to_vec();
+            cells.sort_unstable();
+            cells
+        };
+        let grid = output("grid");
+        let sparse = output("sparse");
+        fs::remove_file(&path).unwrap();
+        assert_eq!(grid.len(), 121);
+        assert_eq!(sparse, grid);
+    }
+
+    #[test]
+    fn parses_help() {
+        assert!(parse(&["-h"]).unwrap().is_none());
+        assert!(parse(&["glider.rle", "--help"]).unwrap().is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        assert!(parse(&[]).is_err());
+        assert!(parse(&["-n"]).is_err());
+        assert!(parse(&["-n", "many", "a.rle"]).is_err());
+        assert!(parse(&["-r", "B9", "a.rle"]).is_err());
+        assert!(parse(&["-t", "sphere", "a.rle"]).is_err());
+        assert!(parse(&["-s", "10", "a.rle"]).is_err());
+        assert!(parse(&["-o", "png", "a.rle"]).is_err());
+        assert!(parse(&["--verbose", "a.rle"]).is_err());
+        assert!(parse(&["a.rle", "b.rle"]).is_err());
+    }
+}
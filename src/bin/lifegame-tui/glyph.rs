@@ -0,0 +1,68 @@
+/// glyph.rs
+/// - Characters which draw several cells in a character cell of the terminal.
+use rust_lifegame::model::{LifeGame, Universe};
+
+/// How the cells are packed into the characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Glyphs {
+    /// Upper and lower half blocks, 1 x 2 cells per character.
+    #[default]
+    HalfBlock,
+    /// Braille patterns, 2 x 4 cells per character.
+    Braille,
+}
+
+/// Bits of the Braille dots, indexed by the row and the column in the character.
+const BRAILLE_DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+impl Glyphs {
+    pub const ALL: [Glyphs; 2] = [Glyphs::HalfBlock, Glyphs::Braille];
+
+    /// Get the name of the glyphs.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Glyphs::HalfBlock => "Half blocks",
+            Glyphs::Braille => "Braille",
+        }
+    }
+    /// Get the glyphs after this.
+    pub fn next(&self) -> Glyphs {
+        let index = Glyphs::ALL.iter().position(|glyphs| glyphs == self).unwrap_or_default();
+        Glyphs::ALL[(index + 1) % Glyphs::ALL.len()]
+    }
+    /// Get the number of the columns and the rows of the cells in a character.
+    pub fn get_cell_size(&self) -> (usize, usize) {
+        match self {
+            Glyphs::HalfBlock => (1, 2),
+            Glyphs::Braille => (2, 4),
+        }
+    }
+    /// Get the character which draws the cells whose top-left corner is at the position.
+    /// The cells outside of the board are drawn dead, without wrapping.
+    pub fn get_char(&self, life_game: &LifeGame, x: i32, y: i32) -> char {
+        let (width, height) = (life_game.get_width() as i32, life_game.get_height() as i32);
+        let alive = |dx: i32, dy: i32| {
+            let (x, y) = (x + dx, y + dy);
+            (0..width).contains(&x) && (0..height).contains(&y) && life_game.is_alive(x, y)
+        };
+        match self {
+            Glyphs::HalfBlock => match (alive(0, 0), alive(0, 1)) {
+                (true, true) => '█',
+                (true, false) => '▀',
+                (false, true) => '▄',
+                (false, false) => ' ',
+            },
+            Glyphs::Braille => {
+                let mut code = 0x2800;
+                for (dy, row) in BRAILLE_DOTS.iter().enumerate() {
+                    for (dx, dot) in row.iter().enumerate() {
+                        if alive(dx as i32, dy as i32) {
+                            code |= dot;
+                        }
+                    }
+                }
+                char::from_u32(code).unwrap_or(' ')
+            }
+        }
+    }
+}
@@ -0,0 +1,301 @@
+/// main.rs
+/// - Terminal frontend of the life game, for the sessions without a display.
+mod glyph;
+mod terminal;
+
+use std::fs;
+use std::io;
+use std::process::ExitCode;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use glyph::Glyphs;
+use rust_lifegame::format;
+use rust_lifegame::model::{Command, History, LifeGame, Random, Rule, Soup, Topology, Universe};
+use terminal::{Key, Terminal};
+
+const USAGE: &str = "\
+Usage: lifegame-tui [OPTIONS] [PATTERN]
+
+Options:
+  -r, --rule <RULE>          Rule such as B3/S23 [default: rule of the pattern, or B3/S23]
+  -t, --topology <TOPOLOGY>  torus, plane, cylinder, klein-bottle or cross-surface [default: torus]
+  -h, --help                 Print this help
+
+Keys:
+  Space start/stop   n step   b step back   + - speed
+  Arrows/hjkl move   Enter/x toggle cell   u undo   U redo
+  r random soup   c clear   g glyphs   q quit";
+
+/// Delays between the generations, from the slowest to as fast as possible.
+const SPEEDS: [Duration; 9] = [
+    Duration::from_secs(4),
+    Duration::from_secs(2),
+    Duration::from_secs(1),
+    Duration::from_millis(500),
+    Duration::from_millis(200),
+    Duration::from_millis(100),
+    Duration::from_millis(50),
+    Duration::from_millis(20),
+    Duration::ZERO,
+];
+/// Speed at the start.
+const DEFAULT_SPEED: usize = 5;
+/// Minimum time between the frames, so that the fastest runs skip drawing.
+const FRAME_TIME: Duration = Duration::from_millis(33);
+
+/// State of the terminal frontend.
+struct App {
+    life_game: LifeGame,
+    history: History,
+    glyphs: Glyphs,
+    /// Position of the cell under the cursor.
+    cursor: (i32, i32),
+    /// Position of the cell at the top-left corner of the screen.
+    origin: (i32, i32),
+    running: bool,
+    speed: usize,
+    message: String,
+    quit: bool,
+}
+
+impl App {
+    fn new(life_game: LifeGame) -> App {
+        let cursor = (life_game.get_width() as i32 / 2, life_game.get_height() as i32 / 2);
+        App {
+            life_game,
+            history: History::default(),
+            glyphs: Glyphs::default(),
+            cursor,
+            origin: (0, 0),
+            running: false,
+            speed: DEFAULT_SPEED,
+            message: String::new(),
+            quit: false,
+        }
+    }
+
+    /// Advance a generation, stopping if every cell died.
+    fn step(&mut self) {
+        self.history.record_step(self.life_game.snapshot());
+        self.life_game.next_generation();
+        if !self.life_game.keep_alive() {
+            self.running = false;
+            self.message = format!("died out at generation {}", self.life_game.get_generation());
+        }
+    }
+
+    /// Move the cursor within the board.
+    fn move_cursor(&mut self, dx: i32, dy: i32) {
+        let (width, height) = (self.life_game.get_width() as i32, self.life_game.get_height() as i32);
+        self.cursor = ((self.cursor.0 + dx).clamp(0, width - 1), (self.cursor.1 + dy).clamp(0, height - 1));
+    }
+
+    /// Handle the key.
+    fn handle_key(&mut self, key: Key) {
+        self.message.clear();
+        match key {
+            Key::Char('q') | Key::Interrupt => self.quit = true,
+            Key::Char(' ') => self.running = !self.running,
+            Key::Char('n') => {
+                self.running = false;
+                self.step();
+            }
+            Key::Char('b') => {
+                self.running = false;
+                if !self.history.step_back(&mut self.life_game) {
+                    self.message = "no previous generation".to_string();
+                }
+            }
+            Key::Char('+') | Key::Char('=') => self.speed = (self.speed + 1).min(SPEEDS.len() - 1),
+            Key::Char('-') => self.speed = self.speed.saturating_sub(1),
+            Key::Up | Key::Char('k') => self.move_cursor(0, -1),
+            Key::Down | Key::Char('j') => self.move_cursor(0, 1),
+            Key::Left | Key::Char('h') => self.move_cursor(-1, 0),
+            Key::Right | Key::Char('l') => self.move_cursor(1, 0),
+            Key::Enter | Key::Char('x') => {
+                let (x, y) = self.cursor;
+                let alive = self.life_game.is_alive(x, y);
+                self.life_game.set_alive(x, y, !alive);
+                self.history.record(Command::Toggle(vec![(x, y)]));
+            }
+            Key::Char('u') => {
+                if !self.history.undo(&mut self.life_game) {
+                    self.message = "nothing to undo".to_string();
+                }
+                self.running = false;
+            }
+            Key::Char('U') => {
+                if !self.history.redo(&mut self.life_game) {
+                    self.message = "nothing to redo".to_string();
+                }
+                self.running = false;
+            }
+            Key::Char('r') => {
+                let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_nanos());
+                let soup = Soup::new(Random::new(nanos as u64).next_u64());
+                let toggled = self.life_game.fill_soup(&soup, None);
+                self.history.record(Command::Toggle(toggled));
+                self.message = format!("soup of seed {}", soup.get_seed());
+            }
+            Key::Char('c') => {
+                let before = self.life_game.snapshot();
+                self.life_game.reset();
+                self.history.record_replace(before, &self.life_game);
+            }
+            Key::Char('g') => self.glyphs = self.glyphs.next(),
+            _ => {}
+        }
+    }
+
+    /// Scroll the screen so that the cursor is visible, for the screen of `columns` x `rows` cells.
+    fn follow_cursor(&mut self, columns: i32, rows: i32) {
+        let (x, y) = self.cursor;
+        let (width, height) = (self.life_game.get_width() as i32, self.life_game.get_height() as i32);
+        let scroll = |origin: i32, position: i32, size: i32, board: i32| {
+            let origin = origin.min(position).max(position - size + 1);
+            origin.min(board - size).max(0)
+        };
+        self.origin = (scroll(self.origin.0, x, columns, width), scroll(self.origin.1, y, rows, height));
+    }
+
+    /// Draw the board and the status line on the screen.
+    fn render(&mut self, terminal: &mut Terminal) -> io::Result<()> {
+        let (columns, rows) = terminal.get_size();
+        let rows = rows.saturating_sub(1).max(1);
+        let (cell_width, cell_height) = self.glyphs.get_cell_size();
+        self.follow_cursor((columns * cell_width) as i32, (rows * cell_height) as i32);
+        let cursor = (
+            (self.cursor.0 - self.origin.0) as usize / cell_width,
+            (self.cursor.1 - self.origin.1) as usize / cell_height,
+        );
+        let mut frame = String::from("\x1b[H");
+        for row in 0..rows {
+            for column in 0..columns {
+                let x = self.origin.0 + (column * cell_width) as i32;
+                let y = self.origin.1 + (row * cell_height) as i32;
+                let glyph = self.glyphs.get_char(&self.life_game, x, y);
+                if (column, row) == cursor {
+                    frame.push_str("\x1b[7m");
+                    frame.push(glyph);
+                    frame.push_str("\x1b[27m");
+                } else {
+                    frame.push(glyph);
+                }
+            }
+            frame.push_str("\r\n");
+        }
+        let delay = SPEEDS[self.speed];
+        let speed = if delay.is_zero() { "max".to_string() } else { format!("{} ms", delay.as_millis()) };
+        let status = format!(
+            " {} | gen {} | pop {} | {} {} | {} | {} | ({}, {}) | {}",
+            if self.running { "running" } else { "paused" },
+            self.life_game.get_generation(),
+            self.life_game.population(),
+            self.life_game.get_rule(),
+            self.life_game.get_topology(),
+            speed,
+            self.glyphs.name(),
+            self.cursor.0,
+            self.cursor.1,
+            self.message,
+        );
+        frame.push_str("\x1b[7m");
+        frame.extend(status.chars().chain(std::iter::repeat(' ')).take(columns));
+        frame.push_str("\x1b[0m");
+        terminal.write(&frame)
+    }
+
+    /// Run the main loop until the quit key is pressed.
+    fn run(&mut self, terminal: &mut Terminal) -> io::Result<()> {
+        let mut last_step = Instant::now();
+        while !self.quit {
+            self.render(terminal)?;
+            let delay = SPEEDS[self.speed];
+            let timeout = self.running.then(|| delay.saturating_sub(last_step.elapsed()));
+            if let Some(key) = terminal.read_key(timeout)? {
+                self.handle_key(key);
+                continue;
+            }
+            if self.running && last_step.elapsed() >= delay {
+                let frame_start = Instant::now();
+                self.step();
+                while delay.is_zero() && self.running && frame_start.elapsed() < FRAME_TIME {
+                    self.step();
+                }
+                last_step = Instant::now();
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Options given on the command line.
+struct Options {
+    path: Option<String>,
+    rule: Option<Rule>,
+    topology: Topology,
+}
+
+/// Parse the command line arguments.
+/// Returns `Ok(None)` if the help is requested.
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Option<Options>, String> {
+    let mut options = Options { path: None, rule: None, topology: Topology::default() };
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("missing value of {}", arg));
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-r" | "--rule" => {
+                let rule = value()?;
+                options.rule = Some(rule.parse().map_err(|error| format!("invalid rule {}: {}", rule, error))?);
+            }
+            "-t" | "--topology" => {
+                let topology = value()?;
+                options.topology = Topology::from_name(&topology).ok_or(format!("unknown topology {}", topology))?;
+            }
+            _ if arg.starts_with('-') => return Err(format!("unknown option {}", arg)),
+            _ if options.path.is_none() => options.path = Some(arg),
+            _ => return Err(format!("unexpected argument {}", arg)),
+        }
+    }
+    Ok(Some(options))
+}
+
+/// Create the board filling the terminal, with the pattern loaded into it.
+fn create_life_game(options: &Options, terminal: &Terminal) -> Result<LifeGame, String> {
+    let (columns, rows) = terminal.get_size();
+    let (cell_width, cell_height) = Glyphs::default().get_cell_size();
+    let mut life_game = LifeGame::new(columns * cell_width, rows.saturating_sub(1).max(1) * cell_height);
+    life_game.set_topology(options.topology);
+    if let Some(path) = &options.path {
+        let text = fs::read_to_string(path).map_err(|error| format!("{}: {}", path, error))?;
+        let pattern = format::parse(&text).map_err(|error| format!("{}: {}", path, error))?;
+        pattern.load_into(&mut life_game).map_err(|error| format!("{}: {}", path, error))?;
+    }
+    if let Some(rule) = options.rule {
+        life_game.set_rule(rule);
+    }
+    Ok(life_game)
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(error) => {
+            eprintln!("error: {}\n\n{}", error, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    let result = Terminal::new().map_err(|error| error.to_string()).and_then(|mut terminal| {
+        let mut app = App::new(create_life_game(&options, &terminal)?);
+        app.run(&mut terminal).map_err(|error| error.to_string())
+    });
+    if let Err(error) = result {
+        eprintln!("error: {}", error);
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,123 @@
+/// terminal.rs
+/// - Raw mode, size and key input of the terminal, with the ANSI escape sequences.
+#[cfg(not(unix))]
+compile_error!("the terminal frontend needs the termios of Unix");
+
+use std::io::{self, Read, Write};
+use std::time::Duration;
+
+/// Key pressed on the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    Up,
+    Down,
+    Left,
+    Right,
+    Enter,
+    Escape,
+    /// Ctrl+C, which does not raise the signal in the raw mode.
+    Interrupt,
+}
+
+/// Terminal in the raw mode on the alternate screen.
+/// The original mode and screen are restored when it is dropped.
+pub struct Terminal {
+    original: libc::termios,
+    input: Vec<u8>,
+}
+
+impl Terminal {
+    /// Switch the terminal to the raw mode and the alternate screen.
+    pub fn new() -> io::Result<Terminal> {
+        // SAFETY: `termios` is a plain C struct, and it is filled by `tcgetattr` before it is used.
+        let mut original: libc::termios = unsafe { std::mem::zeroed() };
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut original) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let mut raw = original;
+        raw.c_lflag &= !(libc::ICANON | libc::ECHO | libc::ISIG | libc::IEXTEN);
+        raw.c_iflag &= !(libc::IXON | libc::ICRNL);
+        raw.c_cc[libc::VMIN] = 1;
+        raw.c_cc[libc::VTIME] = 0;
+        // SAFETY: `raw` is a valid `termios` copied from the one filled by `tcgetattr`, and is only read.
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSAFLUSH, &raw) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let mut terminal = Terminal { original, input: Vec::new() };
+        terminal.write("\x1b[?1049h\x1b[?25l\x1b[2J")?;
+        Ok(terminal)
+    }
+
+    /// Get the number of the columns and the rows.
+    pub fn get_size(&self) -> (usize, usize) {
+        // SAFETY: `winsize` is a plain C struct filled by the `TIOCGWINSZ` request.
+        let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+        if unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) } != 0 || size.ws_col == 0 {
+            return (80, 24);
+        }
+        (size.ws_col as usize, size.ws_row as usize)
+    }
+
+    /// Write the text to the terminal at once.
+    pub fn write(&mut self, text: &str) -> io::Result<()> {
+        let mut out = io::stdout().lock();
+        out.write_all(text.as_bytes())?;
+        out.flush()
+    }
+
+    /// Wait for a key up to the timeout, or forever if the timeout is `None`.
+    pub fn read_key(&mut self, timeout: Option<Duration>) -> io::Result<Option<Key>> {
+        if self.input.is_empty() {
+            let mut poll = libc::pollfd { fd: libc::STDIN_FILENO, events: libc::POLLIN, revents: 0 };
+            let timeout = timeout.map_or(-1, |timeout| timeout.as_millis().min(i32::MAX as u128) as i32);
+            // SAFETY: `poll` points to a single `pollfd` which lives on the stack for the whole call,
+            // and the count of 1 matches it, so the kernel writes `revents` only within it.
+            match unsafe { libc::poll(&mut poll, 1, timeout) } {
+                -1 => {
+                    let error = io::Error::last_os_error();
+                    return if error.kind() == io::ErrorKind::Interrupted { Ok(None) } else { Err(error) };
+                }
+                0 => return Ok(None),
+                _ => {}
+            }
+            let mut buffer = [0u8; 64];
+            let length = io::stdin().lock().read(&mut buffer)?;
+            self.input.extend_from_slice(&buffer[..length]);
+        }
+        Ok(self.parse_key())
+    }
+
+    /// Take a key from the start of the input.
+    fn parse_key(&mut self) -> Option<Key> {
+        let (key, length) = match self.input[..] {
+            [] => return None,
+            [0x1b, b'[', b'A', ..] => (Some(Key::Up), 3),
+            [0x1b, b'[', b'B', ..] => (Some(Key::Down), 3),
+            [0x1b, b'[', b'C', ..] => (Some(Key::Right), 3),
+            [0x1b, b'[', b'D', ..] => (Some(Key::Left), 3),
+            [0x1b, b'[', ..] => {
+                // Skip the other control sequences up to the final byte.
+                let end = self.input[2..].iter().position(|byte| (0x40..=0x7e).contains(byte));
+                (None, end.map_or(self.input.len(), |end| end + 3))
+            }
+            [0x1b, ..] => (Some(Key::Escape), 1),
+            [0x03, ..] => (Some(Key::Interrupt), 1),
+            [b'\r' | b'\n', ..] => (Some(Key::Enter), 1),
+            [byte, ..] if byte.is_ascii() => (Some(Key::Char(byte as char)), 1),
+            _ => (None, 1),
+        };
+        self.input.drain(..length);
+        key
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let _ = self.write("\x1b[0m\x1b[?25h\x1b[?1049l");
+        // SAFETY: `original` is the `termios` filled by `tcgetattr` when the terminal was created.
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSAFLUSH, &self.original);
+        }
+    }
+}
@@ -0,0 +1,423 @@
+/// board.rs
+/// - Component which draws the whole board in a single DrawingArea.
+use gtk::prelude::*;
+use gtk::DrawingArea;
+use cairo::{Context, Filter, Format, ImageSurface};
+use relm4::{
+    gtk,
+    ComponentParts,
+    ComponentSender,
+    SimpleComponent,
+};
+use std::rc::Rc;
+use std::cell::RefCell;
+use crate::model::{BitGrid, BoundingBox};
+use super::viewport::Viewport;
+
+const ALIVE_COLOR: (f64, f64, f64) = (0.0, 1.0, 0.8);
+const DEAD_COLOR: (f64, f64, f64) = (0.5, 0.5, 0.5);
+const GRID_COLOR: (f64, f64, f64) = (0.2, 0.2, 0.2);
+const BACKGROUND_COLOR: (f64, f64, f64) = (0.15, 0.15, 0.15);
+/// Color of the stamp preview, drawn over the cells.
+const STAMP_COLOR: (f64, f64, f64, f64) = (1.0, 0.8, 0.0, 0.5);
+/// Zoom factor of a mouse wheel or key step.
+const ZOOM_STEP: f64 = 1.2;
+/// Pan distance of a key step in pixels.
+const PAN_STEP: f64 = 32.0;
+/// The smallest cell size to draw the grid lines.
+const GRID_MIN_CELL_SIZE: f64 = 6.0;
+
+/// Cells shown on the board, their image of one pixel per cell and the viewport.
+#[derive(Debug)]
+struct BoardState {
+    cells: BitGrid,
+    image: Option<ImageSurface>,
+    viewport: Viewport,
+    is_fitted: bool,
+    /// Cells of the pattern to stamp, with the top-left corner at the origin.
+    stamp: Option<Vec<(i32, i32)>>,
+    /// Cell under the mouse pointer.
+    hover: Option<(i32, i32)>,
+}
+
+impl BoardState {
+    /// Get the cell at the widget position.
+    fn cell_at(&self, x: f64, y: f64) -> Option<(i32, i32)> {
+        let (column, row) = self.viewport.cell_at(x, y);
+        let inside = (0..self.cells.get_width() as i32).contains(&column)
+            && (0..self.cells.get_height() as i32).contains(&row);
+        inside.then_some((column, row))
+    }
+    /// Get the top-left cell of the stamp centered on the cell under the mouse pointer.
+    fn stamp_origin(&self) -> Option<(i32, i32)> {
+        let (column, row) = self.hover?;
+        let bounds = BoundingBox::from_cells(self.stamp.as_ref()?.iter().copied())?;
+        Some((column - bounds.get_width() as i32 / 2, row - bounds.get_height() as i32 / 2))
+    }
+    /// Fit the alive cells, or the whole board if there is none, into the widget.
+    fn fit_pattern(&mut self, width: f64, height: f64) {
+        let mut alive = Vec::new();
+        for y in 0..self.cells.get_height() {
+            for x in 0..self.cells.get_width() {
+                if self.cells.get(x, y) {
+                    alive.push((x as i32, y as i32));
+                }
+            }
+        }
+        let ((left, top), (right, bottom)) = match BoundingBox::from_cells(alive) {
+            Some(bounds) => (bounds.get_min(), bounds.get_max()),
+            None => ((0, 0), (self.cells.get_width() as i32 - 1, self.cells.get_height() as i32 - 1)),
+        };
+        let margin = 1.0;
+        self.viewport.fit(
+            (left as f64 - margin, top as f64 - margin),
+            (right as f64 + 1.0 + margin, bottom as f64 + 1.0 + margin),
+            width,
+            height,
+        );
+        self.is_fitted = true;
+    }
+    /// Replace the cells and repaint the image, if any of them changed.
+    /// The widget is redrawn as a whole, so the image is repainted as a whole too.
+    fn update(&mut self, cells: BitGrid) -> bool {
+        if self.image.is_some() && self.cells == cells {
+            return false;
+        }
+        if self.cells.get_width() != cells.get_width() || self.cells.get_height() != cells.get_height() {
+            self.is_fitted = false;
+        }
+        self.cells = cells;
+        self.image = self.paint_all();
+        true
+    }
+    /// Paint all the cells into a new image.
+    fn paint_all(&self) -> Option<ImageSurface> {
+        let width = self.cells.get_width().max(1) as i32;
+        let height = self.cells.get_height().max(1) as i32;
+        let image = ImageSurface::create(Format::Rgb24, width, height).ok()?;
+        {
+            let cr = Context::new(&image).ok()?;
+            cr.set_source_rgb(DEAD_COLOR.0, DEAD_COLOR.1, DEAD_COLOR.2);
+            cr.paint().ok()?;
+            for y in 0..self.cells.get_height() {
+                for x in 0..self.cells.get_width() {
+                    if self.cells.get(x, y) {
+                        paint_cell(&cr, x, y, true);
+                    }
+                }
+            }
+        }
+        Some(image)
+    }
+}
+
+fn paint_cell(cr: &Context, x: usize, y: usize, is_alive: bool) {
+    let (r, g, b) = if is_alive { ALIVE_COLOR } else { DEAD_COLOR };
+    cr.set_source_rgb(r, g, b);
+    cr.rectangle(x as f64, y as f64, 1.0, 1.0);
+    cr.fill().expect("Failed to fill rectangle.");
+}
+
+fn draw_board(state: &mut BoardState, cr: &Context, width: i32, height: i32) {
+    if !state.is_fitted && width > 0 && height > 0 {
+        state.fit_pattern(width as f64, height as f64);
+    }
+    cr.set_source_rgb(BACKGROUND_COLOR.0, BACKGROUND_COLOR.1, BACKGROUND_COLOR.2);
+    cr.paint().expect("Failed to paint background.");
+    let Some(image) = &state.image else {
+        return;
+    };
+    let viewport = state.viewport;
+    let scale = viewport.get_scale();
+    let (offset_x, offset_y) = viewport.get_offset();
+    let columns = state.cells.get_width() as f64;
+    let rows = state.cells.get_height() as f64;
+    cr.save().expect("Failed to save context.");
+    cr.translate(offset_x, offset_y);
+    cr.scale(scale, scale);
+    cr.set_source_surface(image, 0.0, 0.0).expect("Failed to set source surface.");
+    cr.source().set_filter(Filter::Nearest);
+    cr.rectangle(0.0, 0.0, columns, rows);
+    cr.fill().expect("Failed to fill rectangle.");
+    if let (Some(stamp), Some((left, top))) = (&state.stamp, state.stamp_origin()) {
+        cr.set_source_rgba(STAMP_COLOR.0, STAMP_COLOR.1, STAMP_COLOR.2, STAMP_COLOR.3);
+        for &(x, y) in stamp {
+            cr.rectangle((left + x) as f64, (top + y) as f64, 1.0, 1.0);
+        }
+        cr.fill().expect("Failed to fill stamp.");
+    }
+    cr.restore().expect("Failed to restore context.");
+    if scale >= GRID_MIN_CELL_SIZE {
+        // Draw only the grid lines in the widget.
+        let (left, top) = viewport.widget_to_board(0.0, 0.0);
+        let (right, bottom) = viewport.widget_to_board(width as f64, height as f64);
+        let (left, right) = (left.floor().max(0.0), right.ceil().min(columns));
+        let (top, bottom) = (top.floor().max(0.0), bottom.ceil().min(rows));
+        cr.set_source_rgb(GRID_COLOR.0, GRID_COLOR.1, GRID_COLOR.2);
+        cr.set_line_width(1.0);
+        for column in left as i64..=right as i64 {
+            let (x, y) = viewport.board_to_widget(column as f64, top);
+            cr.move_to(x, y);
+            cr.line_to(x, viewport.board_to_widget(column as f64, bottom).1);
+        }
+        for row in top as i64..=bottom as i64 {
+            let (x, y) = viewport.board_to_widget(left, row as f64);
+            cr.move_to(x, y);
+            cr.line_to(viewport.board_to_widget(right, row as f64).0, y);
+        }
+        cr.stroke().expect("Failed to stroke grid.");
+    }
+}
+
+#[derive(Debug)]
+pub struct BoardModel {
+    state: Rc<RefCell<BoardState>>,
+    drawing_area: DrawingArea,
+    pressed_cell: Option<(i32, i32)>,
+    pointer: (f64, f64),
+    pan_start: (f64, f64),
+    is_event_accept: bool,
+}
+
+#[derive(Debug)]
+pub enum BoardMsg {
+    Update(BitGrid),
+    MousePressed { x: f64, y: f64 },
+    MouseReleased { x: f64, y: f64 },
+    MouseMoved { x: f64, y: f64 },
+    MouseLeft,
+    AcceptClick(bool),
+    /// Zoom by the factor around the mouse pointer.
+    Zoom(f64),
+    /// Zoom by the factor around the center of the widget.
+    ZoomCenter(f64),
+    Pan { dx: f64, dy: f64 },
+    DragBegin,
+    DragUpdate { dx: f64, dy: f64 },
+    FitPattern,
+    CenterOrigin,
+    /// Set the cells of the pattern to stamp on click, or `None` to toggle the cells.
+    SetStamp(Option<Vec<(i32, i32)>>),
+}
+
+#[derive(Debug)]
+pub enum BoardOutputMsg {
+    StateChanged { column: i32, row: i32, alive: bool },
+    /// The stamp is placed with its top-left corner at the cell.
+    Stamp { column: i32, row: i32 },
+    /// The stamp tool is cancelled with the Escape key.
+    StampCancelled,
+}
+
+#[relm4::component(pub)]
+impl SimpleComponent for BoardModel {
+    type Init = BitGrid;
+    type Input = BoardMsg;
+    type Output = BoardOutputMsg;
+
+    view! {
+        #[root]
+        DrawingArea {
+            set_content_width: 400,
+            set_content_height: 400,
+            set_hexpand: true,
+            set_vexpand: true,
+            set_focusable: true,
+        }
+    }
+
+    fn init(
+        cells: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let mut state = BoardState {
+            cells,
+            image: None,
+            viewport: Viewport::default(),
+            is_fitted: false,
+            stamp: None,
+            hover: None,
+        };
+        state.image = state.paint_all();
+        let model = BoardModel {
+            state: Rc::new(RefCell::new(state)),
+            drawing_area: root.clone(),
+            pressed_cell: None,
+            pointer: (0.0, 0.0),
+            pan_start: (0.0, 0.0),
+            is_event_accept: true,
+        };
+        let widgets = view_output!();
+        let state = model.state.clone();
+        root.set_draw_func(move |_area, cr, width, height| {
+            draw_board(&mut state.borrow_mut(), cr, width, height);
+        });
+        model.setup_mouse_events(&sender);
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>) {
+        match msg {
+            BoardMsg::Update(cells) => {
+                if self.state.borrow_mut().update(cells) {
+                    self.drawing_area.queue_draw();
+                }
+            }
+            BoardMsg::MousePressed { x, y } => {
+                self.drawing_area.grab_focus();
+                self.pressed_cell = self.cell_at(x, y);
+            }
+            BoardMsg::MouseReleased { x, y } => {
+                let pressed_cell = self.pressed_cell.take();
+                if !self.is_event_accept || pressed_cell.is_none() || pressed_cell != self.cell_at(x, y) {
+                    return;
+                }
+                if let Some((column, row)) = self.state.borrow().stamp_origin() {
+                    _ = sender.output(BoardOutputMsg::Stamp { column, row });
+                    return;
+                }
+                if let Some((column, row)) = pressed_cell {
+                    let mut state = self.state.borrow_mut();
+                    let mut cells = state.cells.clone();
+                    let alive = !cells.get(column as usize, row as usize);
+                    cells.set(column as usize, row as usize, alive);
+                    state.update(cells);
+                    self.drawing_area.queue_draw();
+                    _ = sender.output(BoardOutputMsg::StateChanged { column, row, alive });
+                }
+            }
+            BoardMsg::MouseMoved { x, y } => {
+                self.pointer = (x, y);
+                let mut state = self.state.borrow_mut();
+                let hover = Some(state.viewport.cell_at(x, y));
+                if state.stamp.is_some() && state.hover != hover {
+                    self.drawing_area.queue_draw();
+                }
+                state.hover = hover;
+            }
+            BoardMsg::MouseLeft => {
+                self.state.borrow_mut().hover = None;
+                self.drawing_area.queue_draw();
+            }
+            BoardMsg::AcceptClick(accept) => {
+                self.is_event_accept = accept;
+            }
+            BoardMsg::Zoom(factor) => {
+                let (x, y) = self.pointer;
+                self.state.borrow_mut().viewport.zoom_at(factor, x, y);
+                self.drawing_area.queue_draw();
+            }
+            BoardMsg::ZoomCenter(factor) => {
+                let (width, height) = self.size();
+                self.state.borrow_mut().viewport.zoom_at(factor, width / 2.0, height / 2.0);
+                self.drawing_area.queue_draw();
+            }
+            BoardMsg::Pan { dx, dy } => {
+                self.state.borrow_mut().viewport.pan(dx, dy);
+                self.drawing_area.queue_draw();
+            }
+            BoardMsg::DragBegin => {
+                self.pan_start = self.state.borrow().viewport.get_offset();
+            }
+            BoardMsg::DragUpdate { dx, dy } => {
+                let (x, y) = self.pan_start;
+                self.state.borrow_mut().viewport.set_offset(x + dx, y + dy);
+                self.drawing_area.queue_draw();
+            }
+            BoardMsg::FitPattern => {
+                let (width, height) = self.size();
+                self.state.borrow_mut().fit_pattern(width, height);
+                self.drawing_area.queue_draw();
+            }
+            BoardMsg::CenterOrigin => {
+                let (width, height) = self.size();
+                self.state.borrow_mut().viewport.center_on(0.0, 0.0, width, height);
+                self.drawing_area.queue_draw();
+            }
+            BoardMsg::SetStamp(stamp) => {
+                self.state.borrow_mut().stamp = stamp;
+                self.drawing_area.queue_draw();
+            }
+        }
+    }
+}
+
+impl BoardModel {
+    fn cell_at(&self, x: f64, y: f64) -> Option<(i32, i32)> {
+        self.state.borrow().cell_at(x, y)
+    }
+    fn size(&self) -> (f64, f64) {
+        (self.drawing_area.width() as f64, self.drawing_area.height() as f64)
+    }
+    fn setup_mouse_events(&self, sender: &ComponentSender<Self>) {
+        let click_controller = gtk::GestureClick::new();
+        let sender_clone = sender.clone();
+        click_controller.connect_pressed(move |_gesture, _n_press, x, y| {
+            sender_clone.input(BoardMsg::MousePressed { x, y });
+        });
+        let sender_clone = sender.clone();
+        click_controller.connect_released(move |_gesture, _n_press, x, y| {
+            sender_clone.input(BoardMsg::MouseReleased { x, y });
+        });
+        self.drawing_area.add_controller(click_controller);
+
+        let motion_controller = gtk::EventControllerMotion::new();
+        let sender_clone = sender.clone();
+        motion_controller.connect_motion(move |_controller, x, y| {
+            sender_clone.input(BoardMsg::MouseMoved { x, y });
+        });
+        let sender_clone = sender.clone();
+        motion_controller.connect_leave(move |_controller| {
+            sender_clone.input(BoardMsg::MouseLeft);
+        });
+        self.drawing_area.add_controller(motion_controller);
+
+        // Zoom with the mouse wheel, centered on the mouse pointer.
+        let scroll_controller = gtk::EventControllerScroll::new(gtk::EventControllerScrollFlags::VERTICAL);
+        let sender_clone = sender.clone();
+        scroll_controller.connect_scroll(move |_controller, _dx, dy| {
+            sender_clone.input(BoardMsg::Zoom(ZOOM_STEP.powf(-dy)));
+            gtk::glib::Propagation::Stop
+        });
+        self.drawing_area.add_controller(scroll_controller);
+
+        // Pan with the middle button drag.
+        let drag_controller = gtk::GestureDrag::new();
+        drag_controller.set_button(gtk::gdk::BUTTON_MIDDLE);
+        let sender_clone = sender.clone();
+        drag_controller.connect_drag_begin(move |_gesture, _x, _y| {
+            sender_clone.input(BoardMsg::DragBegin);
+        });
+        let sender_clone = sender.clone();
+        drag_controller.connect_drag_update(move |_gesture, dx, dy| {
+            sender_clone.input(BoardMsg::DragUpdate { dx, dy });
+        });
+        self.drawing_area.add_controller(drag_controller);
+
+        // Pan with the arrow keys and zoom with +/-.
+        let key_controller = gtk::EventControllerKey::new();
+        let sender_clone = sender.clone();
+        let output_sender = sender.output_sender().clone();
+        key_controller.connect_key_pressed(move |_controller, key, _code, _state| {
+            if key == gtk::gdk::Key::Escape {
+                _ = output_sender.send(BoardOutputMsg::StampCancelled);
+                return gtk::glib::Propagation::Stop;
+            }
+            let msg = match key {
+                gtk::gdk::Key::Left => BoardMsg::Pan { dx: PAN_STEP, dy: 0.0 },
+                gtk::gdk::Key::Right => BoardMsg::Pan { dx: -PAN_STEP, dy: 0.0 },
+                gtk::gdk::Key::Up => BoardMsg::Pan { dx: 0.0, dy: PAN_STEP },
+                gtk::gdk::Key::Down => BoardMsg::Pan { dx: 0.0, dy: -PAN_STEP },
+                gtk::gdk::Key::plus | gtk::gdk::Key::equal | gtk::gdk::Key::KP_Add => BoardMsg::ZoomCenter(ZOOM_STEP),
+                gtk::gdk::Key::minus | gtk::gdk::Key::KP_Subtract => BoardMsg::ZoomCenter(1.0 / ZOOM_STEP),
+                gtk::gdk::Key::f => BoardMsg::FitPattern,
+                gtk::gdk::Key::Home | gtk::gdk::Key::_0 => BoardMsg::CenterOrigin,
+                _ => return gtk::glib::Propagation::Proceed,
+            };
+            sender_clone.input(msg);
+            gtk::glib::Propagation::Stop
+        });
+        self.drawing_area.add_controller(key_controller);
+    }
+}
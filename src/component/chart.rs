@@ -0,0 +1,117 @@
+/// chart.rs
+/// - Component which draws the scrolling chart of the population.
+use gtk::prelude::*;
+use gtk::DrawingArea;
+use cairo::Context;
+use relm4::{
+    gtk,
+    ComponentParts,
+    ComponentSender,
+    SimpleComponent,
+};
+use std::rc::Rc;
+use std::cell::RefCell;
+
+const LINE_COLOR: (f64, f64, f64) = (0.0, 1.0, 0.8);
+const AXIS_COLOR: (f64, f64, f64) = (0.5, 0.5, 0.5);
+const BACKGROUND_COLOR: (f64, f64, f64) = (0.15, 0.15, 0.15);
+/// Space around the plot in pixels.
+const MARGIN: f64 = 4.0;
+
+/// Draw the population of the generations, scaled to fit the widget.
+fn draw_chart(points: &[(u64, usize)], cr: &Context, width: i32, height: i32) {
+    cr.set_source_rgb(BACKGROUND_COLOR.0, BACKGROUND_COLOR.1, BACKGROUND_COLOR.2);
+    cr.paint().expect("Failed to paint background.");
+    let (Some(&(first, _)), Some(&(last, _))) = (points.first(), points.last()) else {
+        return;
+    };
+    let peak = points.iter().map(|&(_, population)| population).max().unwrap_or_default().max(1);
+    let (width, height) = (width as f64 - MARGIN * 2.0, height as f64 - MARGIN * 2.0);
+    let span = (last - first).max(1) as f64;
+    let to_widget = |generation: u64, population: usize| {
+        (
+            MARGIN + (generation - first) as f64 / span * width,
+            MARGIN + height - population as f64 / peak as f64 * height,
+        )
+    };
+
+    cr.set_source_rgb(AXIS_COLOR.0, AXIS_COLOR.1, AXIS_COLOR.2);
+    cr.set_line_width(1.0);
+    cr.move_to(MARGIN, MARGIN + height);
+    cr.line_to(MARGIN + width, MARGIN + height);
+    cr.stroke().expect("Failed to stroke axis.");
+    cr.set_font_size(10.0);
+    cr.move_to(MARGIN + 2.0, MARGIN + 10.0);
+    cr.show_text(&format!("{}", peak)).expect("Failed to show text.");
+    let label = format!("{} - {}", first, last);
+    if let Ok(extents) = cr.text_extents(&label) {
+        cr.move_to(MARGIN + width - extents.width() - 2.0, MARGIN + height - 2.0);
+        cr.show_text(&label).expect("Failed to show text.");
+    }
+
+    cr.set_source_rgb(LINE_COLOR.0, LINE_COLOR.1, LINE_COLOR.2);
+    cr.set_line_width(1.5);
+    for (i, &(generation, population)) in points.iter().enumerate() {
+        let (x, y) = to_widget(generation, population);
+        if i == 0 {
+            cr.move_to(x, y);
+        } else {
+            cr.line_to(x, y);
+        }
+    }
+    cr.stroke().expect("Failed to stroke line.");
+}
+
+#[derive(Debug)]
+pub struct ChartModel {
+    /// The generations and their populations, from the oldest.
+    points: Rc<RefCell<Vec<(u64, usize)>>>,
+    drawing_area: DrawingArea,
+}
+
+#[derive(Debug)]
+pub enum ChartMsg {
+    /// Replace the generations and their populations.
+    Update(Vec<(u64, usize)>),
+}
+
+#[relm4::component(pub)]
+impl SimpleComponent for ChartModel {
+    type Init = ();
+    type Input = ChartMsg;
+    type Output = ();
+
+    view! {
+        #[root]
+        DrawingArea {
+            set_content_height: 120,
+            set_hexpand: true,
+        }
+    }
+
+    fn init(
+        _init: Self::Init,
+        root: Self::Root,
+        _sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = ChartModel {
+            points: Rc::new(RefCell::new(Vec::new())),
+            drawing_area: root.clone(),
+        };
+        let widgets = view_output!();
+        let points = model.points.clone();
+        root.set_draw_func(move |_area, cr, width, height| {
+            draw_chart(&points.borrow(), cr, width, height);
+        });
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, msg: Self::Input, _sender: ComponentSender<Self>) {
+        match msg {
+            ChartMsg::Update(points) => {
+                *self.points.borrow_mut() = points;
+                self.drawing_area.queue_draw();
+            }
+        }
+    }
+}
@@ -1,5 +1,9 @@
-pub mod cell_factory;
+pub mod board;
+pub mod chart;
+pub mod viewport;
 
-pub use cell_factory::CellMsg;
-pub use cell_factory::CellModel;
-pub use cell_factory::CellOutputMsg;
\ No newline at end of file
+pub use board::BoardMsg;
+pub use board::BoardModel;
+pub use board::BoardOutputMsg;
+pub use chart::ChartModel;
+pub use chart::ChartMsg;
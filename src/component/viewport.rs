@@ -0,0 +1,75 @@
+/// viewport.rs
+/// - Transform between the board coordinates and the widget coordinates.
+///
+/// Viewport of the board.
+/// A cell `(x, y)` is drawn at `offset + (x, y) * scale` in the widget.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    scale: f64,
+    offset_x: f64,
+    offset_y: f64,
+}
+
+/// The smallest cell size in pixels.
+const MIN_SCALE: f64 = 0.05;
+/// The largest cell size in pixels.
+const MAX_SCALE: f64 = 200.0;
+
+impl Default for Viewport {
+    fn default() -> Viewport {
+        Viewport { scale: 20.0, offset_x: 0.0, offset_y: 0.0 }
+    }
+}
+
+impl Viewport {
+    /// Get the cell size in pixels.
+    pub fn get_scale(&self) -> f64 {
+        self.scale
+    }
+    /// Get the widget position of the board origin.
+    pub fn get_offset(&self) -> (f64, f64) {
+        (self.offset_x, self.offset_y)
+    }
+    /// Convert the board position into the widget position.
+    pub fn board_to_widget(&self, x: f64, y: f64) -> (f64, f64) {
+        (self.offset_x + x * self.scale, self.offset_y + y * self.scale)
+    }
+    /// Convert the widget position into the board position.
+    pub fn widget_to_board(&self, x: f64, y: f64) -> (f64, f64) {
+        ((x - self.offset_x) / self.scale, (y - self.offset_y) / self.scale)
+    }
+    /// Get the cell at the widget position.
+    pub fn cell_at(&self, x: f64, y: f64) -> (i32, i32) {
+        let (x, y) = self.widget_to_board(x, y);
+        (x.floor() as i32, y.floor() as i32)
+    }
+    /// Zoom by `factor`, keeping the board position under the widget position `(x, y)`.
+    pub fn zoom_at(&mut self, factor: f64, x: f64, y: f64) {
+        let (board_x, board_y) = self.widget_to_board(x, y);
+        self.scale = (self.scale * factor).clamp(MIN_SCALE, MAX_SCALE);
+        self.offset_x = x - board_x * self.scale;
+        self.offset_y = y - board_y * self.scale;
+    }
+    /// Move the board by `(dx, dy)` pixels.
+    pub fn pan(&mut self, dx: f64, dy: f64) {
+        self.offset_x += dx;
+        self.offset_y += dy;
+    }
+    /// Set the widget position of the board origin.
+    pub fn set_offset(&mut self, offset_x: f64, offset_y: f64) {
+        self.offset_x = offset_x;
+        self.offset_y = offset_y;
+    }
+    /// Fit the board rectangle `(left, top)` - `(right, bottom)` (exclusive) into the widget.
+    pub fn fit(&mut self, (left, top): (f64, f64), (right, bottom): (f64, f64), width: f64, height: f64) {
+        let columns = (right - left).max(1.0);
+        let rows = (bottom - top).max(1.0);
+        self.scale = (width / columns).min(height / rows).clamp(MIN_SCALE, MAX_SCALE);
+        self.center_on((left + right) / 2.0, (top + bottom) / 2.0, width, height);
+    }
+    /// Move the board position `(x, y)` to the center of the widget.
+    pub fn center_on(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        self.offset_x = width / 2.0 - x * self.scale;
+        self.offset_y = height / 2.0 - y * self.scale;
+    }
+}
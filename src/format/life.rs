@@ -0,0 +1,207 @@
+/// life.rs
+/// - Life 1.05 and Life 1.06 pattern formats.
+use std::collections::HashSet;
+
+use super::{FormatError, PatternData};
+use crate::model::Rule;
+
+/// Header line of the Life 1.05 format.
+pub const LIFE_105_HEADER: &str = "#Life 1.05";
+/// Header line of the Life 1.06 format.
+pub const LIFE_106_HEADER: &str = "#Life 1.06";
+
+/// Parse a `#` line shared by both formats. Unknown lines are ignored.
+fn parse_hash_line(line: &str, pattern: &mut PatternData) -> Result<(), FormatError> {
+    if let Some(description) = line.strip_prefix("#D") {
+        pattern.add_description(description.trim())?;
+    } else if line == "#N" {
+        pattern.set_rule(Some(Rule::CONWAY));
+    } else if let Some(rule) = line.strip_prefix("#R") {
+        pattern.set_rule(Some(rule.trim().parse::<Rule>()?));
+    }
+    Ok(())
+}
+
+/// Write the `#` lines shared by both formats.
+fn write_hash_lines(pattern: &PatternData, header: &str) -> String {
+    let mut text = format!("{}\n", header);
+    for description in pattern.get_descriptions() {
+        text.push_str(&format!("#D {}\n", description));
+    }
+    match pattern.get_rule() {
+        Some(rule) if *rule == Rule::CONWAY => text.push_str("#N\n"),
+        Some(rule) => {
+            // The rule is written in the survival/birth notation.
+            let digits = |counts: Vec<u8>| counts.iter().map(|n| n.to_string()).collect::<String>();
+            text.push_str(&format!("#R {}/{}\n", digits(rule.get_survival()), digits(rule.get_birth())));
+        }
+        None => {}
+    }
+    text
+}
+
+/// Parse the pattern in the Life 1.05 format.
+pub fn parse_life_105(text: &str) -> Result<PatternData, FormatError> {
+    let mut pattern = PatternData::default();
+    let mut cells = Vec::new();
+    let (mut block_x, mut y) = (0, 0);
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(LIFE_105_HEADER) {
+            continue;
+        }
+        if let Some(position) = line.strip_prefix("#P") {
+            let mut numbers = position.split_whitespace().map(|number| number.parse::<i32>());
+            match (numbers.next(), numbers.next()) {
+                (Some(Ok(x)), Some(Ok(top))) => {
+                    block_x = x;
+                    y = top;
+                }
+                _ => return Err(FormatError::InvalidNumber { line: line_number }),
+            }
+            continue;
+        }
+        if line.starts_with('#') {
+            parse_hash_line(line, &mut pattern)?;
+            continue;
+        }
+        for (x, character) in line.chars().enumerate() {
+            match character {
+                '.' => {}
+                '*' | 'O' => cells.push((block_x + x as i32, y)),
+                character => {
+                    return Err(FormatError::UnexpectedCharacter { line: line_number, character });
+                }
+            }
+        }
+        y += 1;
+    }
+    Ok(PatternData { cells, ..pattern })
+}
+
+/// Write the pattern in the Life 1.05 format as a single block.
+pub fn write_life_105(pattern: &PatternData) -> String {
+    let mut text = write_hash_lines(pattern, LIFE_105_HEADER);
+    let Some(bounds) = pattern.get_bounding_box() else {
+        return text;
+    };
+    let alive: HashSet<(i32, i32)> = pattern.get_cells().iter().copied().collect();
+    let ((left, top), (right, bottom)) = (bounds.get_min(), bounds.get_max());
+    text.push_str(&format!("#P {} {}\n", left, top));
+    for y in top..=bottom {
+        let row: String = (left..=right)
+            .map(|x| if alive.contains(&(x, y)) { '*' } else { '.' })
+            .collect();
+        let row = row.trim_end_matches('.');
+        text.push_str(if row.is_empty() { "." } else { row });
+        text.push('\n');
+    }
+    text
+}
+
+/// Parse the pattern in the Life 1.06 format.
+pub fn parse_life_106(text: &str) -> Result<PatternData, FormatError> {
+    let mut pattern = PatternData::default();
+    let mut cells = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(LIFE_106_HEADER) {
+            continue;
+        }
+        if line.starts_with('#') {
+            parse_hash_line(line, &mut pattern)?;
+            continue;
+        }
+        let mut numbers = line.split_whitespace().map(|number| number.parse::<i32>());
+        match (numbers.next(), numbers.next(), numbers.next()) {
+            (Some(Ok(x)), Some(Ok(y)), None) => cells.push((x, y)),
+            _ => return Err(FormatError::InvalidNumber { line: index + 1 }),
+        }
+    }
+    Ok(PatternData { cells, ..pattern })
+}
+
+/// Write the pattern in the Life 1.06 format.
+/// The metadata is written as the `#D` and `#N`/`#R` lines of the Life 1.05 format.
+pub fn write_life_106(pattern: &PatternData) -> String {
+    let mut text = write_hash_lines(pattern, LIFE_106_HEADER);
+    let mut cells = pattern.get_cells().to_vec();
+    cells.sort_by_key(|&(x, y)| (y, x));
+    for (x, y) in cells {
+        text.push_str(&format!("{} {}\n", x, y));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(pattern: &PatternData) -> Vec<(i32, i32)> {
+        let mut cells = pattern.get_cells().to_vec();
+        cells.sort_unstable();
+        cells
+    }
+
+    fn glider() -> PatternData {
+        let mut pattern = PatternData::new(vec![(-1, -2), (0, -1), (-2, 0), (-1, 0), (0, 0), (3, 4)]);
+        pattern.set_name(Some("Glider".to_string()));
+        pattern.add_comment("The smallest spaceship.".to_string());
+        pattern
+    }
+
+    #[test]
+    fn round_trips_life_105() {
+        for rule in [Rule::CONWAY, "B36/S23".parse().unwrap()] {
+            let mut pattern = glider();
+            pattern.set_rule(Some(rule));
+            let text = write_life_105(&pattern);
+            assert!(text.starts_with(LIFE_105_HEADER));
+            let parsed = parse_life_105(&text).unwrap();
+            // The block keeps the position of the cells.
+            assert_eq!(sorted(&parsed), sorted(&pattern));
+            assert_eq!(parsed.get_name(), Some("Glider"));
+            assert_eq!(parsed.get_comments(), ["The smallest spaceship."]);
+            assert_eq!(parsed.get_rule(), Some(&rule));
+        }
+    }
+
+    #[test]
+    fn round_trips_life_106() {
+        for rule in [Rule::CONWAY, "B36/S23".parse().unwrap()] {
+            let mut pattern = glider();
+            pattern.set_rule(Some(rule));
+            let text = write_life_106(&pattern);
+            assert!(text.starts_with(LIFE_106_HEADER));
+            let parsed = parse_life_106(&text).unwrap();
+            assert_eq!(sorted(&parsed), sorted(&pattern));
+            assert_eq!(parsed.get_name(), Some("Glider"));
+            assert_eq!(parsed.get_rule(), Some(&rule));
+        }
+    }
+
+    #[test]
+    fn reads_several_blocks_of_life_105() {
+        let pattern = parse_life_105("#Life 1.05\n#P -1 -1\n.*\n#P 10 0\n**\n").unwrap();
+        assert_eq!(sorted(&pattern), vec![(0, -1), (10, 0), (11, 0)]);
+    }
+
+    #[test]
+    fn rejects_truncated_lines() {
+        assert_eq!(parse_life_105("#Life 1.05\n#P 1\n*\n"), Err(FormatError::InvalidNumber { line: 2 }));
+        assert_eq!(parse_life_106("#Life 1.06\n0 0\n1\n"), Err(FormatError::InvalidNumber { line: 3 }));
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        assert_eq!(
+            parse_life_105("#Life 1.05\n#P 0 0\n*x\n"),
+            Err(FormatError::UnexpectedCharacter { line: 3, character: 'x' })
+        );
+        assert_eq!(parse_life_105("#Life 1.05\n#P a 0\n"), Err(FormatError::InvalidNumber { line: 2 }));
+        assert_eq!(parse_life_106("#Life 1.06\n1 2 3\n"), Err(FormatError::InvalidNumber { line: 2 }));
+        assert_eq!(parse_life_106("#Life 1.06\n1 9999999999\n"), Err(FormatError::InvalidNumber { line: 2 }));
+        assert!(matches!(parse_life_106("#Life 1.06\n#R 23/9\n"), Err(FormatError::InvalidRule(_))));
+    }
+}
@@ -0,0 +1,184 @@
+/// macrocell.rs
+/// - Golly macrocell (.mc) pattern format.
+use super::FormatError;
+use crate::model::{HashLife, Rule, TreeNode, Universe};
+
+/// Header line of the macrocell format.
+pub const MACROCELL_HEADER: &str = "[M2]";
+
+/// Parse the 8 x 8 leaf line such as `$$..*$.*.*$`.
+fn parse_leaf(line: &str, line_number: usize) -> Result<TreeNode, FormatError> {
+    let mut rows = [0u8; 8];
+    let (mut x, mut y) = (0, 0);
+    for character in line.chars() {
+        match character {
+            '$' => {
+                x = 0;
+                y += 1;
+            }
+            '.' | '*' if x < 8 && y < 8 => {
+                if character == '*' {
+                    rows[y] |= 1 << x;
+                }
+                x += 1;
+            }
+            character => return Err(FormatError::UnexpectedCharacter { line: line_number, character }),
+        }
+    }
+    Ok(TreeNode::Leaf(rows))
+}
+
+/// Parse the node line `level nw ne sw se`.
+fn parse_branch(line: &str, line_number: usize) -> Result<TreeNode, FormatError> {
+    let invalid = FormatError::InvalidNumber { line: line_number };
+    let numbers = line
+        .split_whitespace()
+        .map(|number| number.parse::<usize>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| invalid.clone())?;
+    match numbers[..] {
+        [level, nw, ne, sw, se] => Ok(TreeNode::Branch {
+            level: u8::try_from(level).map_err(|_| invalid.clone())?,
+            children: [nw, ne, sw, se],
+        }),
+        _ => Err(invalid),
+    }
+}
+
+/// Parse the pattern in the macrocell format into a HashLife universe,
+/// applying the `#R` rule and the `#G` generation counter.
+pub fn parse_macrocell(text: &str) -> Result<HashLife, FormatError> {
+    let mut lines = text.lines().enumerate();
+    match lines.next() {
+        Some((_, header)) if header.trim().starts_with(MACROCELL_HEADER) => {}
+        Some((_, header)) => return Err(FormatError::InvalidHeader(header.to_string())),
+        None => return Err(FormatError::InvalidHeader(String::new())),
+    }
+    let mut rule = None;
+    let mut generation = 0;
+    let mut tree = Vec::new();
+    let mut line_numbers = Vec::new();
+    for (index, line) in lines {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix('#') {
+            let mut chars = rest.chars();
+            let kind = chars.next();
+            let value = chars.as_str().trim();
+            match kind {
+                Some('R') => rule = Some(value.parse::<Rule>()?),
+                Some('G') => {
+                    generation = value.parse::<u64>().map_err(|_| FormatError::InvalidNumber { line: line_number })?;
+                }
+                _ => {}
+            }
+            continue;
+        }
+        let node = if line.starts_with(|character: char| character.is_ascii_digit()) {
+            parse_branch(line, line_number)?
+        } else {
+            parse_leaf(line, line_number)?
+        };
+        tree.push(node);
+        line_numbers.push(line_number);
+    }
+    let mut hash_life =
+        HashLife::from_tree(&tree).map_err(|index| FormatError::InvalidNumber { line: line_numbers[index] })?;
+    if let Some(rule) = rule {
+        hash_life.set_rule(rule)?;
+    }
+    hash_life.set_generation(generation);
+    Ok(hash_life)
+}
+
+/// Write the 8 x 8 leaf line, omitting the trailing dead cells and rows.
+fn write_leaf(rows: &[u8; 8]) -> String {
+    let height = rows.iter().rposition(|&row| row != 0).map_or(0, |y| y + 1);
+    rows[..height]
+        .iter()
+        .map(|&row| {
+            let width = 8 - row.leading_zeros() as usize;
+            let cells: String = (0..width).map(|x| if row & (1 << x) != 0 { '*' } else { '.' }).collect();
+            cells + "$"
+        })
+        .collect()
+}
+
+/// Write the quadtree in the macrocell format with the rule and the generation counter.
+pub fn write_macrocell_tree(tree: &[TreeNode], rule: &Rule, generation: u64) -> String {
+    let mut text = format!("{} (rust_lifegame)\n#R {}\n", MACROCELL_HEADER, rule);
+    if generation > 0 {
+        text.push_str(&format!("#G {}\n", generation));
+    }
+    for node in tree {
+        match node {
+            TreeNode::Leaf(rows) => text.push_str(&write_leaf(rows)),
+            TreeNode::Branch { level, children: [nw, ne, sw, se] } => {
+                text.push_str(&format!("{} {} {} {} {}", level, nw, ne, sw, se));
+            }
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// Write the HashLife universe in the macrocell format.
+pub fn write_macrocell(hash_life: &HashLife) -> String {
+    write_macrocell_tree(&hash_life.to_tree(), hash_life.get_rule(), hash_life.get_generation())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::RuleError;
+
+    fn sorted<U: Universe>(universe: &U) -> Vec<(i32, i32)> {
+        let mut cells = universe.live_cells();
+        cells.sort_unstable();
+        cells
+    }
+
+    fn error(text: &str) -> Option<FormatError> {
+        parse_macrocell(text).err()
+    }
+
+    #[test]
+    fn round_trips_the_cells_the_rule_and_the_generation() {
+        let mut hash_life = HashLife::from_cells([(1, 0), (2, 1), (0, 2), (1, 2), (2, 2), (100000, -300)]);
+        hash_life.set_rule("B36/S23".parse().unwrap()).unwrap();
+        hash_life.set_generation(42);
+        let text = write_macrocell(&hash_life);
+        assert!(text.starts_with(MACROCELL_HEADER));
+        let parsed = parse_macrocell(&text).unwrap();
+        assert_eq!(sorted(&parsed), sorted(&hash_life));
+        assert_eq!(parsed.get_rule(), hash_life.get_rule());
+        assert_eq!(parsed.get_generation(), 42);
+    }
+
+    #[test]
+    fn round_trips_an_empty_universe() {
+        let parsed = parse_macrocell(&write_macrocell(&HashLife::new())).unwrap();
+        assert_eq!(parsed.population(), 0);
+        assert_eq!(parsed.get_generation(), 0);
+    }
+
+    #[test]
+    fn rejects_truncated_patterns() {
+        assert_eq!(error(""), Some(FormatError::InvalidHeader(String::new())));
+        assert_eq!(error("[M2]\n$$..*$\n4 1 0 0\n"), Some(FormatError::InvalidNumber { line: 3 }));
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        assert_eq!(error("x = 3, y = 3\n"), Some(FormatError::InvalidHeader("x = 3, y = 3".to_string())));
+        // A node may refer only to the nodes before it, of the level below.
+        assert_eq!(error("[M2]\n4 1 0 0 0\n"), Some(FormatError::InvalidNumber { line: 2 }));
+        assert_eq!(error("[M2]\n$$*$\n5 1 0 0 0\n"), Some(FormatError::InvalidNumber { line: 3 }));
+        assert_eq!(error("[M2]\n.........$\n"), Some(FormatError::UnexpectedCharacter { line: 2, character: '.' }));
+        assert_eq!(error("[M2]\n#G many\n"), Some(FormatError::InvalidNumber { line: 2 }));
+        assert_eq!(error("[M2]\n#R B03/S23\n"), Some(FormatError::InvalidRule(RuleError::UnsupportedB0)));
+    }
+}
@@ -0,0 +1,335 @@
+/// format/mod.rs
+///  - Pattern file formats.
+mod life;
+mod macrocell;
+mod plaintext;
+mod rle;
+
+use std::fmt;
+
+use crate::model::{Anchor, BoundingBox, HashLife, LifeGame, Rule, RuleError, Universe, MAX_BOARD_SIZE};
+
+pub use life::{parse_life_105, parse_life_106, write_life_105, write_life_106};
+pub use macrocell::{parse_macrocell, write_macrocell, write_macrocell_tree};
+pub use plaintext::{parse_plaintext, write_plaintext};
+pub use rle::{parse_rle, write_rle};
+
+/// Empty cells kept around a loaded pattern when the board is grown.
+const LOAD_MARGIN: usize = 4;
+
+/// Error of reading a pattern file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormatError {
+    /// The header line is malformed.
+    InvalidHeader(String),
+    /// The rule of the pattern is invalid.
+    InvalidRule(RuleError),
+    /// The character is not allowed at the line.
+    UnexpectedCharacter { line: usize, character: char },
+    /// The number at the line is malformed or too large.
+    InvalidNumber { line: usize },
+    /// The pattern is larger than the largest board.
+    TooLarge { width: u64, height: u64 },
+    /// The pattern ends before its end mark, e.g. the file is truncated.
+    UnexpectedEnd,
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatError::InvalidHeader(header) => write!(f, "invalid header '{}'", header),
+            FormatError::InvalidRule(error) => write!(f, "invalid rule: {}", error),
+            FormatError::UnexpectedCharacter { line, character } => {
+                write!(f, "unexpected character '{}' at line {}", character, line)
+            }
+            FormatError::InvalidNumber { line } => write!(f, "invalid number at line {}", line),
+            FormatError::TooLarge { width, height } => write!(
+                f,
+                "the pattern of {} x {} cells is larger than the board of {} x {} cells",
+                width, height, MAX_BOARD_SIZE, MAX_BOARD_SIZE
+            ),
+            FormatError::UnexpectedEnd => write!(f, "the pattern ends unexpectedly"),
+        }
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+impl From<RuleError> for FormatError {
+    fn from(error: RuleError) -> FormatError {
+        FormatError::InvalidRule(error)
+    }
+}
+
+/// Pattern file format.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    /// Run Length Encoded.
+    #[default]
+    Rle,
+    /// Plaintext (.cells).
+    Plaintext,
+    /// Life 1.05.
+    Life105,
+    /// Life 1.06.
+    Life106,
+    /// Golly macrocell.
+    Macrocell,
+}
+
+impl Format {
+    pub const ALL: [Format; 5] =
+        [Format::Rle, Format::Plaintext, Format::Life105, Format::Life106, Format::Macrocell];
+
+    /// Get the name of the format.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Format::Rle => "RLE",
+            Format::Plaintext => "Plaintext",
+            Format::Life105 => "Life 1.05",
+            Format::Life106 => "Life 1.06",
+            Format::Macrocell => "Macrocell",
+        }
+    }
+    /// Get the file extension of the format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Rle => "rle",
+            Format::Plaintext => "cells",
+            Format::Life105 | Format::Life106 => "lif",
+            Format::Macrocell => "mc",
+        }
+    }
+    /// Detect the format from the content of a pattern file.
+    pub fn detect(text: &str) -> Format {
+        let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+        let Some(first) = lines.next() else {
+            return Format::Rle;
+        };
+        if first.starts_with(life::LIFE_105_HEADER) {
+            Format::Life105
+        } else if first.starts_with(life::LIFE_106_HEADER) {
+            Format::Life106
+        } else if first.starts_with(macrocell::MACROCELL_HEADER) {
+            Format::Macrocell
+        } else if first.starts_with('!') {
+            Format::Plaintext
+        } else if first.starts_with('#') || first.starts_with('x') {
+            Format::Rle
+        } else if first.chars().all(|character| matches!(character, '.' | 'O' | '*')) {
+            Format::Plaintext
+        } else {
+            Format::Rle
+        }
+    }
+    /// Parse the pattern in the format.
+    /// A macrocell pattern larger than the largest board is rejected before its cells are listed,
+    /// since a small file can hold a huge pattern.
+    pub fn parse(&self, text: &str) -> Result<PatternData, FormatError> {
+        match self {
+            Format::Rle => parse_rle(text),
+            Format::Plaintext => parse_plaintext(text),
+            Format::Life105 => parse_life_105(text),
+            Format::Life106 => parse_life_106(text),
+            Format::Macrocell => {
+                let hash_life = parse_macrocell(text)?;
+                check_size(hash_life.bounding_box())?;
+                Ok(PatternData::from_universe(&hash_life))
+            }
+        }
+    }
+    /// Write the pattern in the format.
+    pub fn write(&self, pattern: &PatternData) -> String {
+        match self {
+            Format::Rle => write_rle(pattern),
+            Format::Plaintext => write_plaintext(pattern),
+            Format::Life105 => write_life_105(pattern),
+            Format::Life106 => write_life_106(pattern),
+            Format::Macrocell => {
+                let tree = HashLife::from_cells(pattern.cells.iter().copied()).to_tree();
+                write_macrocell_tree(&tree, &pattern.rule.unwrap_or_default(), pattern.generation)
+            }
+        }
+    }
+}
+
+/// Check that the cells in the bounding box fit on the largest board.
+fn check_size(bounds: Option<BoundingBox>) -> Result<(), FormatError> {
+    match bounds {
+        Some(bounds) if bounds.get_width() > MAX_BOARD_SIZE as u64 || bounds.get_height() > MAX_BOARD_SIZE as u64 => {
+            Err(FormatError::TooLarge { width: bounds.get_width(), height: bounds.get_height() })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Parse the pattern, detecting the format from the content.
+pub fn parse(text: &str) -> Result<PatternData, FormatError> {
+    Format::detect(text).parse(text)
+}
+
+/// Pattern read from or written to a pattern file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PatternData {
+    name: Option<String>,
+    author: Option<String>,
+    comments: Vec<String>,
+    rule: Option<Rule>,
+    generation: u64,
+    cells: Vec<(i32, i32)>,
+}
+
+impl PatternData {
+    /// Create a new pattern with the alive cells.
+    pub fn new(cells: Vec<(i32, i32)>) -> PatternData {
+        PatternData { cells, ..PatternData::default() }
+    }
+    /// Create a new pattern with the alive cells, the rule and the generation of the universe.
+    pub fn from_universe<U: Universe>(universe: &U) -> PatternData {
+        PatternData {
+            rule: Some(*universe.get_rule()),
+            generation: universe.get_generation(),
+            cells: universe.live_cells(),
+            ..PatternData::default()
+        }
+    }
+    /// Get the name of the pattern.
+    pub fn get_name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+    /// Set the name of the pattern.
+    pub fn set_name(&mut self, name: Option<String>) {
+        self.name = name;
+    }
+    /// Get the author of the pattern.
+    pub fn get_author(&self) -> Option<&str> {
+        self.author.as_deref()
+    }
+    /// Set the author of the pattern.
+    pub fn set_author(&mut self, author: Option<String>) {
+        self.author = author;
+    }
+    /// Get the comment lines of the pattern.
+    pub fn get_comments(&self) -> &[String] {
+        &self.comments
+    }
+    /// Add a comment line to the pattern.
+    pub fn add_comment(&mut self, comment: String) {
+        self.comments.push(comment);
+    }
+    /// Get the description lines of the pattern, i.e. `Name: ...` and `Author: ...`
+    /// followed by the comment lines.
+    pub fn get_descriptions(&self) -> Vec<String> {
+        let name = self.name.as_ref().map(|name| format!("Name: {}", name));
+        let author = self.author.as_ref().map(|author| format!("Author: {}", author));
+        name.into_iter().chain(author).chain(self.comments.iter().cloned()).collect()
+    }
+    /// Add a description line to the pattern.
+    /// `Name:`, `Author:` and `Rule:` lines are read as the metadata, the others as comments.
+    pub fn add_description(&mut self, line: &str) -> Result<(), FormatError> {
+        if let Some(name) = line.strip_prefix("Name:") {
+            self.name = Some(name.trim().to_string());
+        } else if let Some(author) = line.strip_prefix("Author:") {
+            self.author = Some(author.trim().to_string());
+        } else if let Some(rule) = line.strip_prefix("Rule:") {
+            self.rule = Some(rule.trim().parse::<Rule>()?);
+        } else {
+            self.comments.push(line.to_string());
+        }
+        Ok(())
+    }
+    /// Get the rule of the pattern, if it is given.
+    pub fn get_rule(&self) -> Option<&Rule> {
+        self.rule.as_ref()
+    }
+    /// Set the rule of the pattern.
+    pub fn set_rule(&mut self, rule: Option<Rule>) {
+        self.rule = rule;
+    }
+    /// Get the generation of the pattern, which is 0 unless the format records it.
+    pub fn get_generation(&self) -> u64 {
+        self.generation
+    }
+    /// Set the generation of the pattern.
+    pub fn set_generation(&mut self, generation: u64) {
+        self.generation = generation;
+    }
+    /// Get the positions of the alive cells.
+    pub fn get_cells(&self) -> &[(i32, i32)] {
+        &self.cells
+    }
+    /// Get the bounding box of the alive cells.
+    pub fn get_bounding_box(&self) -> Option<BoundingBox> {
+        BoundingBox::from_cells(self.cells.iter().copied())
+    }
+
+    /// Load the pattern into the center of the board, growing the board if the pattern
+    /// does not fit, and apply the rule and the generation of the pattern.
+    /// The board is left as it is if the pattern is larger than the largest board.
+    pub fn load_into(&self, life_game: &mut LifeGame) -> Result<(), FormatError> {
+        let bounds = self.get_bounding_box();
+        check_size(bounds)?;
+        life_game.reset();
+        life_game.set_generation(self.generation);
+        if let Some(rule) = self.rule {
+            life_game.set_rule(rule);
+        }
+        let Some(bounds) = bounds else {
+            return Ok(());
+        };
+        let (pattern_width, pattern_height) = (bounds.get_width() as usize, bounds.get_height() as usize);
+        let grown = |size: usize, pattern_size: usize| size.max((pattern_size + LOAD_MARGIN * 2).min(MAX_BOARD_SIZE));
+        let width = grown(life_game.get_width(), pattern_width);
+        let height = grown(life_game.get_height(), pattern_height);
+        if width != life_game.get_width() || height != life_game.get_height() {
+            life_game.resize(width, height, Anchor::TopLeft);
+        }
+        let (left, top) = bounds.get_min();
+        let offset_x = ((width - pattern_width) / 2) as i32 - left;
+        let offset_y = ((height - pattern_height) / 2) as i32 - top;
+        for &(x, y) in &self.cells {
+            life_game.set_alive(x + offset_x, y + offset_y, true);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_into_centers_and_grows_the_board() {
+        let pattern = parse("x = 3, y = 1, rule = B36/S23\no60bo!").unwrap();
+        let mut life_game = LifeGame::new(16, 16);
+        pattern.load_into(&mut life_game).unwrap();
+        assert_eq!((life_game.get_width(), life_game.get_height()), (70, 16));
+        assert_eq!(life_game.live_cells().len(), 2);
+        assert_eq!(life_game.get_rule().to_string(), "B36/S23");
+    }
+
+    #[test]
+    fn load_into_rejects_patterns_larger_than_the_board() {
+        let pattern = parse("x = 3, y = 1\no2000000bo!").unwrap();
+        let mut life_game = LifeGame::new(16, 16);
+        life_game.set_alive(1, 1, true);
+        assert_eq!(pattern.load_into(&mut life_game), Err(FormatError::TooLarge { width: 2000002, height: 1 }));
+        // The board is kept as it is.
+        assert_eq!((life_game.get_width(), life_game.get_height()), (16, 16));
+        assert_eq!(life_game.live_cells(), vec![(1, 1)]);
+
+        let pattern = PatternData::new(vec![(0, 0), (MAX_BOARD_SIZE as i32 - 1, 0)]);
+        pattern.load_into(&mut life_game).unwrap();
+        assert_eq!(life_game.get_width(), MAX_BOARD_SIZE);
+        assert_eq!(life_game.live_cells().len(), 2);
+    }
+
+    #[test]
+    fn parse_rejects_macrocell_patterns_larger_than_the_board() {
+        let far = MAX_BOARD_SIZE as i32;
+        let text = write_macrocell(&HashLife::from_cells([(0, 0), (far, 0)]));
+        assert_eq!(parse(&text), Err(FormatError::TooLarge { width: far as u64 + 1, height: 1 }));
+        let text = write_macrocell(&HashLife::from_cells([(0, 0), (far - 1, 0)]));
+        assert_eq!(parse(&text).unwrap().get_cells().len(), 2);
+    }
+}
@@ -0,0 +1,98 @@
+/// plaintext.rs
+/// - Plaintext (.cells) pattern format.
+use std::collections::HashSet;
+
+use super::{FormatError, PatternData};
+
+/// Parse the pattern in the plaintext format.
+/// `!Name:`, `!Author:` and `!Rule:` comments are read as the metadata.
+pub fn parse_plaintext(text: &str) -> Result<PatternData, FormatError> {
+    let mut pattern = PatternData::default();
+    let mut cells = Vec::new();
+    let mut y = 0;
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim_end();
+        if let Some(comment) = line.strip_prefix('!') {
+            pattern.add_description(comment.trim())?;
+            continue;
+        }
+        for (x, character) in line.chars().enumerate() {
+            match character {
+                '.' => {}
+                'O' | '*' => cells.push((x as i32, y)),
+                character => {
+                    return Err(FormatError::UnexpectedCharacter { line: index + 1, character });
+                }
+            }
+        }
+        y += 1;
+    }
+    Ok(PatternData { cells, ..pattern })
+}
+
+/// Write the pattern in the plaintext format.
+pub fn write_plaintext(pattern: &PatternData) -> String {
+    let mut text = String::new();
+    for description in pattern.get_descriptions() {
+        text.push_str(&format!("!{}\n", description));
+    }
+    if let Some(rule) = pattern.get_rule() {
+        text.push_str(&format!("!Rule: {}\n", rule));
+    }
+    let Some(bounds) = pattern.get_bounding_box() else {
+        return text;
+    };
+    let alive: HashSet<(i32, i32)> = pattern.get_cells().iter().copied().collect();
+    let ((left, top), (right, bottom)) = (bounds.get_min(), bounds.get_max());
+    for y in top..=bottom {
+        let row: String = (left..=right)
+            .map(|x| if alive.contains(&(x, y)) { 'O' } else { '.' })
+            .collect();
+        text.push_str(row.trim_end_matches('.'));
+        text.push('\n');
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(pattern: &PatternData) -> Vec<(i32, i32)> {
+        let mut cells = pattern.get_cells().to_vec();
+        cells.sort_unstable();
+        cells
+    }
+
+    #[test]
+    fn round_trips_the_cells_and_the_metadata() {
+        let mut pattern = PatternData::new(vec![(1, 0), (2, 1), (0, 2), (1, 2), (2, 2), (4, 5)]);
+        pattern.set_name(Some("Glider".to_string()));
+        pattern.set_author(Some("Richard K. Guy".to_string()));
+        pattern.add_comment("www.conwaylife.com".to_string());
+        pattern.set_rule(Some("B36/S23".parse().unwrap()));
+        let text = write_plaintext(&pattern);
+        let parsed = parse_plaintext(&text).unwrap();
+        assert_eq!(sorted(&parsed), sorted(&pattern));
+        assert_eq!(parsed.get_name(), Some("Glider"));
+        assert_eq!(parsed.get_author(), Some("Richard K. Guy"));
+        assert_eq!(parsed.get_comments(), ["www.conwaylife.com"]);
+        assert_eq!(parsed.get_rule(), pattern.get_rule());
+    }
+
+    #[test]
+    fn reads_both_alive_characters() {
+        let pattern = parse_plaintext("!Name: Blinker\n\n*O*\n").unwrap();
+        assert_eq!(sorted(&pattern), vec![(0, 1), (1, 1), (2, 1)]);
+        assert_eq!(pattern.get_rule(), None);
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        assert_eq!(
+            parse_plaintext("!Name: Glider\n.O.\n..x\n"),
+            Err(FormatError::UnexpectedCharacter { line: 3, character: 'x' })
+        );
+        assert!(matches!(parse_plaintext("!Rule: B9/S23\nO\n"), Err(FormatError::InvalidRule(_))));
+    }
+}
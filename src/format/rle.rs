@@ -0,0 +1,267 @@
+/// rle.rs
+/// - Run Length Encoded (RLE) pattern format.
+use std::collections::HashSet;
+
+use super::{FormatError, PatternData};
+use crate::model::Rule;
+
+/// The longest line written to an RLE file.
+const MAX_LINE_LENGTH: usize = 70;
+/// The largest number of alive cells read from an RLE file.
+const MAX_CELLS: usize = 1 << 24;
+
+/// Parse the header line `x = m, y = n, rule = B3/S23`.
+fn parse_header(line: &str, pattern: &mut PatternData) -> Result<(), FormatError> {
+    // A value may contain ',' as well, e.g. `rule = B3/S23:T100,100`.
+    let mut items: Vec<(&str, String)> = Vec::new();
+    for item in line.split(',') {
+        match (item.split_once('='), items.last_mut()) {
+            (Some((key, value)), _) => items.push((key.trim(), value.trim().to_string())),
+            (None, Some((_, value))) => {
+                value.push(',');
+                value.push_str(item.trim());
+            }
+            (None, None) => return Err(FormatError::InvalidHeader(line.to_string())),
+        }
+    }
+    let mut has_size = (false, false);
+    for (key, value) in items {
+        let value = value.as_str();
+        match key {
+            "x" | "y" => {
+                value.parse::<u64>().map_err(|_| FormatError::InvalidHeader(line.to_string()))?;
+                if key == "x" {
+                    has_size.0 = true;
+                } else {
+                    has_size.1 = true;
+                }
+            }
+            "rule" => {
+                // Drop the bounded grid suffix of Golly, e.g. `B3/S23:T100,100`.
+                let rule = value.split(':').next().unwrap_or(value);
+                pattern.set_rule(Some(rule.parse::<Rule>()?));
+            }
+            _ => {}
+        }
+    }
+    if has_size != (true, true) {
+        return Err(FormatError::InvalidHeader(line.to_string()));
+    }
+    Ok(())
+}
+
+/// Parse the pattern in the RLE format.
+/// The pattern must end with `!`, so a truncated file is rejected.
+pub fn parse_rle(text: &str) -> Result<PatternData, FormatError> {
+    let mut pattern = PatternData::default();
+    let mut cells = Vec::new();
+    let (mut x, mut y) = (0i32, 0i32);
+    let mut count: Option<i32> = None;
+    let mut in_body = false;
+    let mut ended = false;
+    'lines: for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if !in_body {
+            if let Some(rest) = line.strip_prefix('#') {
+                let mut chars = rest.chars();
+                let kind = chars.next();
+                let value = chars.as_str().trim().to_string();
+                match kind {
+                    Some('N') => pattern.set_name(Some(value)),
+                    Some('O') => pattern.set_author(Some(value)),
+                    Some('C') | Some('c') => pattern.add_comment(value),
+                    _ => {}
+                }
+                continue;
+            }
+            in_body = true;
+            if line.starts_with('x') {
+                parse_header(line, &mut pattern)?;
+                continue;
+            }
+        }
+        for character in line.chars() {
+            if let Some(digit) = character.to_digit(10) {
+                let value = count
+                    .unwrap_or(0)
+                    .checked_mul(10)
+                    .and_then(|value| value.checked_add(digit as i32))
+                    .ok_or(FormatError::InvalidNumber { line: line_number })?;
+                count = Some(value);
+                continue;
+            }
+            let run = count.take().unwrap_or(1);
+            let invalid = FormatError::InvalidNumber { line: line_number };
+            match character {
+                'b' | '.' => x = x.checked_add(run).ok_or(invalid)?,
+                'o' | 'A'..='X' => {
+                    if cells.len() + run as usize > MAX_CELLS {
+                        return Err(invalid);
+                    }
+                    let end = x.checked_add(run).ok_or(invalid)?;
+                    cells.extend((x..end).map(|x| (x, y)));
+                    x = end;
+                }
+                '$' => {
+                    y = y.checked_add(run).ok_or(invalid)?;
+                    x = 0;
+                }
+                '!' => {
+                    ended = true;
+                    break 'lines;
+                }
+                character if character.is_whitespace() => {}
+                character => {
+                    return Err(FormatError::UnexpectedCharacter { line: line_number, character });
+                }
+            }
+        }
+    }
+    if !ended {
+        return Err(FormatError::UnexpectedEnd);
+    }
+    Ok(PatternData { cells, ..pattern })
+}
+
+/// Append the token to the lines, wrapping the line if it gets too long.
+fn push_token(lines: &mut Vec<String>, token: &str) {
+    match lines.last_mut() {
+        Some(line) if line.len() + token.len() <= MAX_LINE_LENGTH => line.push_str(token),
+        _ => lines.push(token.to_string()),
+    }
+}
+
+/// Get the token of a run of cells.
+fn run_token(count: usize, tag: char) -> String {
+    if count == 1 {
+        tag.to_string()
+    } else {
+        format!("{}{}", count, tag)
+    }
+}
+
+/// Write the pattern in the RLE format.
+pub fn write_rle(pattern: &PatternData) -> String {
+    let mut text = String::new();
+    if let Some(name) = pattern.get_name() {
+        text.push_str(&format!("#N {}\n", name));
+    }
+    if let Some(author) = pattern.get_author() {
+        text.push_str(&format!("#O {}\n", author));
+    }
+    for comment in pattern.get_comments() {
+        text.push_str(&format!("#C {}\n", comment));
+    }
+    let rule = pattern.get_rule().copied().unwrap_or_default();
+    let Some(bounds) = pattern.get_bounding_box() else {
+        text.push_str(&format!("x = 0, y = 0, rule = {}\n!\n", rule));
+        return text;
+    };
+    text.push_str(&format!(
+        "x = {}, y = {}, rule = {}\n",
+        bounds.get_width(),
+        bounds.get_height(),
+        rule
+    ));
+    let alive: HashSet<(i32, i32)> = pattern.get_cells().iter().copied().collect();
+    let ((left, top), (right, bottom)) = (bounds.get_min(), bounds.get_max());
+    let mut lines = Vec::new();
+    let mut pending_rows = 0;
+    for y in top..=bottom {
+        let mut runs: Vec<(usize, bool)> = Vec::new();
+        for x in left..=right {
+            let state = alive.contains(&(x, y));
+            match runs.last_mut() {
+                Some((count, last)) if *last == state => *count += 1,
+                _ => runs.push((1, state)),
+            }
+        }
+        if runs.last().is_some_and(|&(_, state)| !state) {
+            runs.pop();
+        }
+        if y > top {
+            pending_rows += 1;
+        }
+        if runs.is_empty() {
+            continue;
+        }
+        if pending_rows > 0 {
+            push_token(&mut lines, &run_token(pending_rows, '$'));
+            pending_rows = 0;
+        }
+        for (count, state) in runs {
+            push_token(&mut lines, &run_token(count, if state { 'o' } else { 'b' }));
+        }
+    }
+    push_token(&mut lines, "!");
+    for line in lines {
+        text.push_str(&line);
+        text.push('\n');
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(pattern: &PatternData) -> Vec<(i32, i32)> {
+        let mut cells = pattern.get_cells().to_vec();
+        cells.sort_unstable();
+        cells
+    }
+
+    #[test]
+    fn round_trips_the_cells_and_the_metadata() {
+        let mut pattern = PatternData::new(vec![(1, 0), (2, 1), (0, 2), (1, 2), (2, 2), (0, 5)]);
+        pattern.set_name(Some("Glider".to_string()));
+        pattern.set_author(Some("Richard K. Guy".to_string()));
+        pattern.add_comment("The smallest spaceship.".to_string());
+        pattern.set_rule(Some("B36/S23".parse().unwrap()));
+        let text = write_rle(&pattern);
+        assert!(text.contains("x = 3, y = 6, rule = B36/S23\n"));
+        let parsed = parse_rle(&text).unwrap();
+        assert_eq!(sorted(&parsed), sorted(&pattern));
+        assert_eq!(parsed.get_name(), Some("Glider"));
+        assert_eq!(parsed.get_author(), Some("Richard K. Guy"));
+        assert_eq!(parsed.get_comments(), ["The smallest spaceship."]);
+        assert_eq!(parsed.get_rule(), pattern.get_rule());
+    }
+
+    #[test]
+    fn round_trips_long_lines() {
+        let pattern = PatternData::new((0..300).filter(|x| x % 3 != 1).map(|x| (x, x % 7)).collect());
+        let text = write_rle(&pattern);
+        assert!(text.lines().all(|line| line.len() <= MAX_LINE_LENGTH));
+        assert_eq!(sorted(&parse_rle(&text).unwrap()), sorted(&pattern));
+    }
+
+    #[test]
+    fn round_trips_an_empty_pattern() {
+        let parsed = parse_rle(&write_rle(&PatternData::default())).unwrap();
+        assert!(parsed.get_cells().is_empty());
+    }
+
+    #[test]
+    fn rejects_truncated_patterns() {
+        assert_eq!(parse_rle(""), Err(FormatError::UnexpectedEnd));
+        assert_eq!(parse_rle("#N Glider\n"), Err(FormatError::UnexpectedEnd));
+        assert_eq!(parse_rle("x = 3, y = 3\nbo$2bo$3o"), Err(FormatError::UnexpectedEnd));
+        assert_eq!(parse_rle("x = 3\nbo$2bo$3o!"), Err(FormatError::InvalidHeader("x = 3".to_string())));
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        assert_eq!(
+            parse_rle("x = 3, y = 3\nbo$2bz$3o!"),
+            Err(FormatError::UnexpectedCharacter { line: 2, character: 'z' })
+        );
+        assert_eq!(parse_rle("x = 3, y = 1\n99999999999o!"), Err(FormatError::InvalidNumber { line: 2 }));
+        assert_eq!(parse_rle("x = a, y = 1\no!"), Err(FormatError::InvalidHeader("x = a, y = 1".to_string())));
+        assert!(matches!(parse_rle("x = 1, y = 1, rule = B9/S23\no!"), Err(FormatError::InvalidRule(_))));
+    }
+}
@@ -0,0 +1,3 @@
+pub mod format;
+pub mod library;
+pub mod model;
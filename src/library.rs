@@ -0,0 +1,287 @@
+/// library.rs
+/// - Library of named patterns, loaded from pattern files.
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::format::{self, FormatError, PatternData};
+use crate::model::Rule;
+
+/// Patterns embedded in the program.
+static DEFAULT_PATTERNS: [&str; 15] = [
+    include_str!("../patterns/block.rle"),
+    include_str!("../patterns/beehive.rle"),
+    include_str!("../patterns/loaf.rle"),
+    include_str!("../patterns/boat.rle"),
+    include_str!("../patterns/blinker.rle"),
+    include_str!("../patterns/toad.rle"),
+    include_str!("../patterns/beacon.rle"),
+    include_str!("../patterns/pulsar.rle"),
+    include_str!("../patterns/pentadecathlon.rle"),
+    include_str!("../patterns/glider.rle"),
+    include_str!("../patterns/lwss.rle"),
+    include_str!("../patterns/gosper-glider-gun.rle"),
+    include_str!("../patterns/r-pentomino.rle"),
+    include_str!("../patterns/diehard.rle"),
+    include_str!("../patterns/acorn.rle"),
+];
+
+/// File extensions read by the library.
+const EXTENSIONS: [&str; 5] = ["rle", "cells", "lif", "life", "mc"];
+
+/// Category of the patterns without a `Category:` comment.
+const DEFAULT_CATEGORY: &str = "Other";
+
+/// Error of loading the pattern library.
+#[derive(Debug)]
+pub enum LibraryError {
+    /// The file or the directory can not be read.
+    Io(PathBuf, io::Error),
+    /// The pattern file is malformed.
+    Format(PathBuf, FormatError),
+}
+
+impl fmt::Display for LibraryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LibraryError::Io(path, error) => write!(f, "{}: {}", path.display(), error),
+            LibraryError::Format(path, error) => write!(f, "{}: {}", path.display(), error),
+        }
+    }
+}
+
+impl std::error::Error for LibraryError {}
+
+/// Named pattern of the library.
+/// The `Category:` and `Period:` comments of the pattern file are read as the metadata,
+/// and the other comments make up the description.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LibraryPattern {
+    name: String,
+    category: String,
+    description: String,
+    period: Option<u32>,
+    pattern: PatternData,
+}
+
+impl LibraryPattern {
+    /// Create a new library pattern from the pattern data.
+    /// `fallback_name` is used if the pattern has no name.
+    pub fn new(pattern: PatternData, fallback_name: &str) -> LibraryPattern {
+        let mut category = None;
+        let mut period = None;
+        let mut description = Vec::new();
+        for comment in pattern.get_comments() {
+            if let Some(value) = comment.strip_prefix("Category:") {
+                category = Some(value.trim().to_string());
+            } else if let Some(value) = comment.strip_prefix("Period:")
+                && let Ok(value) = value.trim().parse::<u32>()
+            {
+                period = Some(value);
+            } else {
+                description.push(comment.trim());
+            }
+        }
+        LibraryPattern {
+            name: pattern.get_name().unwrap_or(fallback_name).to_string(),
+            category: category.unwrap_or_else(|| DEFAULT_CATEGORY.to_string()),
+            description: description.join(" "),
+            period,
+            pattern,
+        }
+    }
+    /// Get the name of the pattern.
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+    /// Get the category of the pattern, e.g. `Oscillator`.
+    pub fn get_category(&self) -> &str {
+        &self.category
+    }
+    /// Get the description of the pattern.
+    pub fn get_description(&self) -> &str {
+        &self.description
+    }
+    /// Get the rule of the pattern, if it is given.
+    pub fn get_rule(&self) -> Option<&Rule> {
+        self.pattern.get_rule()
+    }
+    /// Get the period of the pattern, if it is periodic.
+    pub fn get_period(&self) -> Option<u32> {
+        self.period
+    }
+    /// Get the cells and the metadata of the pattern.
+    pub fn get_pattern(&self) -> &PatternData {
+        &self.pattern
+    }
+    /// Check if the name, the category or the description contains the query, ignoring case.
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        [&self.name, &self.category, &self.description]
+            .iter()
+            .any(|text| text.to_lowercase().contains(&query))
+    }
+}
+
+/// Library of named patterns.
+#[derive(Debug, Clone, Default)]
+pub struct PatternLibrary {
+    patterns: Vec<LibraryPattern>,
+}
+
+impl PatternLibrary {
+    /// Create a new empty library.
+    pub fn new() -> PatternLibrary {
+        PatternLibrary::default()
+    }
+    /// Create a new library with the embedded default patterns.
+    pub fn with_defaults() -> PatternLibrary {
+        let mut library = PatternLibrary::new();
+        for text in DEFAULT_PATTERNS {
+            let pattern = format::parse(text).expect("embedded pattern is malformed");
+            library.add(LibraryPattern::new(pattern, ""));
+        }
+        library
+    }
+    /// Add the pattern to the library, replacing the pattern of the same name.
+    pub fn add(&mut self, pattern: LibraryPattern) {
+        match self.patterns.iter_mut().find(|other| other.name == pattern.name) {
+            Some(other) => *other = pattern,
+            None => self.patterns.push(pattern),
+        }
+    }
+    /// Load the pattern files in the directory, and get the number of the loaded patterns
+    /// with the errors of the files which were skipped.
+    /// An error is returned only if the directory can not be read.
+    pub fn load_dir(&mut self, dir: &Path) -> Result<(usize, Vec<LibraryError>), LibraryError> {
+        let entries = fs::read_dir(dir).map_err(|error| LibraryError::Io(dir.to_path_buf(), error))?;
+        let mut paths = Vec::new();
+        let mut errors = Vec::new();
+        for entry in entries {
+            let path = match entry {
+                Ok(entry) => entry.path(),
+                Err(error) => {
+                    errors.push(LibraryError::Io(dir.to_path_buf(), error));
+                    continue;
+                }
+            };
+            let extension = path.extension().and_then(|extension| extension.to_str());
+            if path.is_file() && extension.is_some_and(|extension| EXTENSIONS.contains(&extension)) {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+        let mut loaded = 0;
+        for path in paths {
+            let pattern = match fs::read_to_string(&path) {
+                Ok(text) => format::parse(&text).map_err(|error| LibraryError::Format(path.clone(), error)),
+                Err(error) => Err(LibraryError::Io(path.clone(), error)),
+            };
+            match pattern {
+                Ok(pattern) => {
+                    let file_name = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default();
+                    self.add(LibraryPattern::new(pattern, file_name));
+                    loaded += 1;
+                }
+                Err(error) => errors.push(error),
+            }
+        }
+        Ok((loaded, errors))
+    }
+    /// Get all the patterns.
+    pub fn get_patterns(&self) -> &[LibraryPattern] {
+        &self.patterns
+    }
+    /// Get the pattern of the name.
+    pub fn get(&self, name: &str) -> Option<&LibraryPattern> {
+        self.patterns.iter().find(|pattern| pattern.name == name)
+    }
+    /// Get the categories of the patterns in the order of their first appearance.
+    pub fn get_categories(&self) -> Vec<&str> {
+        let mut categories: Vec<&str> = Vec::new();
+        for pattern in &self.patterns {
+            if !categories.contains(&pattern.get_category()) {
+                categories.push(pattern.get_category());
+            }
+        }
+        categories
+    }
+    /// Get the indices of the patterns matching the query.
+    pub fn search(&self, query: &str) -> Vec<usize> {
+        (0..self.patterns.len()).filter(|&index| self.patterns[index].matches(query)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::write_macrocell;
+    use crate::model::{HashLife, MAX_BOARD_SIZE};
+
+    /// Create an empty directory for the test in the temporary directory.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rust-lifegame-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn names(library: &PatternLibrary, indices: Vec<usize>) -> Vec<&str> {
+        indices.into_iter().map(|index| library.get_patterns()[index].get_name()).collect()
+    }
+
+    #[test]
+    fn search_matches_the_name_the_category_and_the_description() {
+        let mut library = PatternLibrary::new();
+        for text in [
+            "#N Glider\n#C Category: Spaceship\n#C The smallest spaceship.\nx = 3, y = 3\nbo$2bo$3o!",
+            "#N Blinker\n#C Category: Oscillator\n#C Period: 2\nx = 3, y = 1\n3o!",
+            "#N Block\nx = 2, y = 2\n2o$2o!",
+        ] {
+            library.add(LibraryPattern::new(format::parse(text).unwrap(), ""));
+        }
+        assert_eq!(names(&library, library.search("glider")), ["Glider"]);
+        assert_eq!(names(&library, library.search(" OSCILLATOR ")), ["Blinker"]);
+        assert_eq!(names(&library, library.search("smallest")), ["Glider"]);
+        assert_eq!(names(&library, library.search("other")), ["Block"]);
+        assert_eq!(names(&library, library.search("")), ["Glider", "Blinker", "Block"]);
+        assert!(library.search("pulsar").is_empty());
+        assert_eq!(library.get("Blinker").unwrap().get_period(), Some(2));
+    }
+
+    #[test]
+    fn load_dir_skips_the_malformed_and_too_large_files() {
+        let dir = temp_dir("load-dir");
+        fs::write(dir.join("glider.rle"), "#N Glider\nx = 3, y = 3\nbo$2bo$3o!").unwrap();
+        fs::write(dir.join("blinker.cells"), "OOO\n").unwrap();
+        fs::write(dir.join("broken.rle"), "x = 3, y = 3\nbo$2bz$3o!").unwrap();
+        let huge = HashLife::from_cells([(0, 0), (MAX_BOARD_SIZE as i32, 0)]);
+        fs::write(dir.join("huge.mc"), write_macrocell(&huge)).unwrap();
+        fs::write(dir.join("notes.txt"), "not a pattern").unwrap();
+
+        let mut library = PatternLibrary::new();
+        let (loaded, errors) = library.load_dir(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(loaded, 2);
+        // The pattern without a name is named after the file.
+        let names: Vec<&str> = library.get_patterns().iter().map(|pattern| pattern.get_name()).collect();
+        assert_eq!(names, ["blinker", "Glider"]);
+        let errors: Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].ends_with("broken.rle: unexpected character 'z' at line 2"), "{}", errors[0]);
+        assert!(errors[1].contains("huge.mc: the pattern of 4097 x 1 cells is larger"), "{}", errors[1]);
+    }
+
+    #[test]
+    fn load_dir_fails_on_a_missing_directory() {
+        let dir = temp_dir("missing").join("missing");
+        assert!(matches!(PatternLibrary::new().load_dir(&dir), Err(LibraryError::Io(..))));
+    }
+
+    #[test]
+    fn embedded_patterns_are_well_formed() {
+        let library = PatternLibrary::with_defaults();
+        assert_eq!(library.get_patterns().len(), DEFAULT_PATTERNS.len());
+    }
+}
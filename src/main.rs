@@ -1,9 +1,9 @@
 use relm4::RelmApp;
 
-mod model;
 mod view;
 mod component;
 
+use rust_lifegame::{format, library, model};
 use view::ViewModel;
 
 fn main() {
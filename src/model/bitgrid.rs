@@ -0,0 +1,341 @@
+/// bitgrid.rs
+/// - Bit-packed board with word-parallel generation stepping.
+use std::ops::Range;
+use std::thread;
+
+use super::{BoundingBox, Rule, Topology};
+
+/// Board which stores one bit per cell in rows of u64 words.
+/// Bit `i` of word `k` in a row is the cell at `x = 64 * k + i`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    stride: usize,
+    words: Vec<u64>,
+}
+
+/// Sum of three bits of each lane: (sum, carry).
+fn full_add(a: u64, b: u64, c: u64) -> (u64, u64) {
+    let ab = a ^ b;
+    (ab ^ c, (a & b) | (c & ab))
+}
+
+/// Select the lanes where the bit is `set`.
+fn select(bit: u64, set: bool) -> u64 {
+    if set { bit } else { !bit }
+}
+
+impl BitGrid {
+    /// Create a new empty board.
+    pub fn new(width: usize, height: usize) -> BitGrid {
+        let stride = width.div_ceil(64);
+        BitGrid {
+            width,
+            height,
+            stride,
+            words: vec![0; stride * height],
+        }
+    }
+    /// Create a board from the words, row by row as returned by `get_words`.
+    /// Returns `None` if the number of the words does not match the size.
+    pub fn from_words(width: usize, height: usize, words: Vec<u64>) -> Option<BitGrid> {
+        let mut bits = BitGrid::new(width, height);
+        if words.len() != bits.words.len() {
+            return None;
+        }
+        bits.words = words;
+        if !width.is_multiple_of(64) {
+            // Kill the cells beyond the right edge.
+            let mask = (1u64 << (width % 64)) - 1;
+            for y in 0..height {
+                bits.words[(y + 1) * bits.stride - 1] &= mask;
+            }
+        }
+        Some(bits)
+    }
+    /// Get the width of the board.
+    pub fn get_width(&self) -> usize {
+        self.width
+    }
+    /// Get the height of the board.
+    pub fn get_height(&self) -> usize {
+        self.height
+    }
+    /// Get the number of words in a row.
+    pub fn get_stride(&self) -> usize {
+        self.stride
+    }
+    /// Get the words of the row.
+    pub fn get_row(&self, y: usize) -> &[u64] {
+        &self.words[y * self.stride..(y + 1) * self.stride]
+    }
+    /// Get all the words, row by row.
+    pub fn get_words(&self) -> &[u64] {
+        &self.words
+    }
+    /// Check if the cell is alive.
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        self.words[y * self.stride + x / 64] & (1 << (x % 64)) != 0
+    }
+    /// Set the cell alive or dead.
+    pub fn set(&mut self, x: usize, y: usize, alive: bool) {
+        let word = &mut self.words[y * self.stride + x / 64];
+        if alive {
+            *word |= 1 << (x % 64);
+        } else {
+            *word &= !(1 << (x % 64));
+        }
+    }
+    /// Kill all the cells.
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+    /// Check if any cell is alive.
+    pub fn any_alive(&self) -> bool {
+        self.words.iter().any(|&word| word != 0)
+    }
+    /// Get the number of alive cells.
+    pub fn count_alive(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+    /// Get the bounding box of the alive cells.
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        let is_empty = |y: &usize| self.get_row(*y).iter().all(|&word| word == 0);
+        let top = (0..self.height).find(|y| !is_empty(y))?;
+        let bottom = (0..self.height).rev().find(|y| !is_empty(y))?;
+        let (mut left, mut right) = (usize::MAX, 0);
+        for y in top..=bottom {
+            for (k, &word) in self.get_row(y).iter().enumerate() {
+                if word != 0 {
+                    left = left.min(k * 64 + word.trailing_zeros() as usize);
+                    right = right.max(k * 64 + 63 - word.leading_zeros() as usize);
+                }
+            }
+        }
+        Some(BoundingBox::new(left as i32, top as i32, right as i32, bottom as i32))
+    }
+
+    /// Get the row `y` with the neighbors on both sides, found through the topology.
+    /// Bit `i` of the result is the cell at `x = i - 1`.
+    fn extended_row(&self, y: i32, topology: Topology) -> Vec<u64> {
+        let (w, h) = (self.width, self.height);
+        let mut extended = vec![0; self.stride + 1];
+        let source = if (0..h as i32).contains(&y) {
+            Some((y as usize, false))
+        } else {
+            match (topology.map(0, y, w, h), topology.map(w as i32 - 1, y, w, h)) {
+                (Some((x, row)), Some(_)) => Some((row as usize, x != 0)),
+                _ => None,
+            }
+        };
+        match source {
+            Some((row, false)) => {
+                for (k, &word) in self.get_row(row).iter().enumerate() {
+                    extended[k] |= word << 1;
+                    extended[k + 1] |= word >> 63;
+                }
+            }
+            Some((row, true)) => {
+                for x in 0..w {
+                    if self.get(w - 1 - x, row) {
+                        extended[(x + 1) / 64] |= 1 << ((x + 1) % 64);
+                    }
+                }
+            }
+            None => {}
+        }
+        for (x, bit) in [(-1, 0), (w as i32, w + 1)] {
+            if let Some((mx, my)) = topology.map(x, y, w, h)
+                && self.get(mx as usize, my as usize)
+            {
+                extended[bit / 64] |= 1 << (bit % 64);
+            }
+        }
+        extended
+    }
+
+    /// Compute the next generation of the rows into `out`, which holds `rows.len()` rows.
+    pub fn step_rows(&self, rule: &Rule, topology: Topology, rows: Range<usize>, out: &mut [u64]) {
+        let counts: Vec<(usize, bool, bool)> = (0..=8)
+            .map(|n| (n, rule.is_birth(n), rule.is_survival(n)))
+            .filter(|&(_, birth, survival)| birth || survival)
+            .collect();
+        let last_mask = match self.width % 64 {
+            0 => !0,
+            bits => (1 << bits) - 1,
+        };
+        let start = rows.start as i32;
+        let mut up = self.extended_row(start - 1, topology);
+        let mut mid = self.extended_row(start, topology);
+        for (i, y) in rows.enumerate() {
+            let down = self.extended_row(y as i32 + 1, topology);
+            for k in 0..self.stride {
+                let left = |row: &[u64]| row[k];
+                let center = |row: &[u64]| (row[k] >> 1) | (row[k + 1] << 63);
+                let right = |row: &[u64]| (row[k] >> 2) | (row[k + 1] << 62);
+                let alive = center(&mid);
+                // Add the 8 neighbor bits of each lane into a 4 bit count.
+                let (s0, c0) = full_add(left(&up), center(&up), right(&up));
+                let (s1, c1) = full_add(left(&mid), right(&mid), left(&down));
+                let (s2, c2) = (center(&down) ^ right(&down), center(&down) & right(&down));
+                let (bit0, c3) = full_add(s0, s1, s2);
+                let (t, c4) = full_add(c0, c1, c2);
+                let (bit1, c5) = (t ^ c3, t & c3);
+                let (bit2, bit3) = (c4 ^ c5, c4 & c5);
+                let mut next = 0;
+                for &(n, birth, survival) in &counts {
+                    let equal = select(bit0, n & 1 != 0)
+                        & select(bit1, n & 2 != 0)
+                        & select(bit2, n & 4 != 0)
+                        & select(bit3, n & 8 != 0);
+                    if birth {
+                        next |= equal & !alive;
+                    }
+                    if survival {
+                        next |= equal & alive;
+                    }
+                }
+                if k == self.stride - 1 {
+                    next &= last_mask;
+                }
+                out[i * self.stride + k] = next;
+            }
+            up = std::mem::replace(&mut mid, down);
+        }
+    }
+
+    /// Compute the next generation of the board.
+    pub fn next_generation(&self, rule: &Rule, topology: Topology) -> BitGrid {
+        let mut next = BitGrid::new(self.width, self.height);
+        self.step_rows(rule, topology, 0..self.height, &mut next.words);
+        next
+    }
+    /// Compute the next generation of the board in bands of `band_height` rows,
+    /// each on its own thread.
+    pub fn next_generation_parallel(&self, rule: &Rule, topology: Topology, band_height: usize) -> BitGrid {
+        let mut next = BitGrid::new(self.width, self.height);
+        let band_height = band_height.max(1);
+        let band_words = (band_height * self.stride).max(1);
+        thread::scope(|scope| {
+            for (i, band) in next.words.chunks_mut(band_words).enumerate() {
+                let start = i * band_height;
+                let rows = start..(start + band_height).min(self.height);
+                scope.spawn(move || self.step_rows(rule, topology, rows, band));
+            }
+        });
+        next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{LifeGame, Random, Storage};
+
+    /// Create a board of the size with random cells.
+    fn random_grid(width: usize, height: usize, seed: u64) -> BitGrid {
+        let mut random = Random::new(seed);
+        let words = (0..width.div_ceil(64) * height).map(|_| random.next_u64()).collect();
+        BitGrid::from_words(width, height, words).unwrap()
+    }
+
+    /// Create a board with the storage and the cells of the grid.
+    fn life_game(grid: &BitGrid, storage: Storage) -> LifeGame {
+        let mut life_game = LifeGame::with_storage(grid.get_width(), grid.get_height(), storage);
+        for y in 0..grid.get_height() {
+            for x in 0..grid.get_width() {
+                if grid.get(x, y) {
+                    life_game.set_alive(x as i32, y as i32, true);
+                }
+            }
+        }
+        life_game
+    }
+
+    #[test]
+    fn parallel_matches_serial() {
+        let rules: [Rule; 2] = ["B3/S23".parse().unwrap(), "B0123/S01234".parse().unwrap()];
+        for (width, height) in [(5, 1), (64, 3), (70, 17), (130, 33)] {
+            for topology in Topology::ALL {
+                for rule in &rules {
+                    let mut serial = random_grid(width, height, (width * height) as u64);
+                    let mut parallel = serial.clone();
+                    for threads in [2, 3, 4, 7, height, height + 5] {
+                        let band_height = height.div_ceil(threads);
+                        serial = serial.next_generation(rule, topology);
+                        parallel = parallel.next_generation_parallel(rule, topology, band_height);
+                        assert_eq!(
+                            serial, parallel,
+                            "{} x {}, {:?}, {}, {} threads",
+                            width, height, topology, rule, threads
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn life_game_threads_match_serial() {
+        let rule: Rule = "B03/S23".parse().unwrap();
+        for (width, height) in [(9, 1), (65, 7), (40, 13)] {
+            let grid = random_grid(width, height, 7);
+            for topology in Topology::ALL {
+                for storage in [Storage::Cells, Storage::BitPacked] {
+                    let mut serial = life_game(&grid, storage);
+                    serial.set_rule(rule);
+                    serial.set_topology(topology);
+                    for _ in 0..3 {
+                        serial.next_generation();
+                    }
+                    for threads in [2, 3, height + 5] {
+                        let mut parallel = life_game(&grid, storage);
+                        parallel.set_rule(rule);
+                        parallel.set_topology(topology);
+                        parallel.set_threads(threads);
+                        for _ in 0..3 {
+                            parallel.next_generation();
+                        }
+                        assert_eq!(
+                            serial.to_bit_grid(),
+                            parallel.to_bit_grid(),
+                            "{} x {}, {:?}, {:?}, {} threads",
+                            width, height, topology, storage, threads
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn storages_match() {
+        let glider = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+        for width in [63, 64, 65] {
+            for topology in Topology::ALL {
+                // A glider alone crosses the right and the bottom edges near the corner.
+                let mut glider_grid = BitGrid::new(width, 11);
+                for (x, y) in glider {
+                    glider_grid.set(width - 3 + x, 8 + y, true);
+                }
+                for grid in [random_grid(width, 11, width as u64), glider_grid] {
+                    let mut cells = life_game(&grid, Storage::Cells);
+                    let mut bits = life_game(&grid, Storage::BitPacked);
+                    cells.set_topology(topology);
+                    bits.set_topology(topology);
+                    for generation in 1..=20 {
+                        cells.next_generation();
+                        bits.next_generation();
+                        assert_eq!(
+                            cells.to_bit_grid(),
+                            bits.to_bit_grid(),
+                            "width {}, {:?}, generation {}",
+                            width, topology, generation
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,265 @@
+/// census.rs
+/// - Census of the objects on a settled board.
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::OnceLock;
+
+use super::{BoundingBox, Rule, SparseLife, Transform, Universe};
+
+/// Known objects of Conway's Life: the name, the cells of a phase and the period.
+static KNOWN_OBJECTS: [(&str, &[&str], u32); 14] = [
+    ("Block", &["OO", "OO"], 1),
+    ("Beehive", &[".OO.", "O..O", ".OO."], 1),
+    ("Loaf", &[".OO.", "O..O", ".O.O", "..O."], 1),
+    ("Boat", &["OO.", "O.O", ".O."], 1),
+    ("Ship", &["OO.", "O.O", ".OO"], 1),
+    ("Tub", &[".O.", "O.O", ".O."], 1),
+    ("Pond", &[".OO.", "O..O", "O..O", ".OO."], 1),
+    ("Blinker", &["OOO"], 2),
+    ("Toad", &[".OOO", "OOO."], 2),
+    ("Beacon", &["OO..", "OO..", "..OO", "..OO"], 2),
+    (
+        "Pulsar",
+        &[
+            "..OOO...OOO..",
+            ".............",
+            "O....O.O....O",
+            "O....O.O....O",
+            "O....O.O....O",
+            "..OOO...OOO..",
+            ".............",
+            "..OOO...OOO..",
+            "O....O.O....O",
+            "O....O.O....O",
+            "O....O.O....O",
+            ".............",
+            "..OOO...OOO..",
+        ],
+        3,
+    ),
+    ("Pentadecathlon", &["..O....O..", "OO.OOOO.OO", "..O....O.."], 15),
+    ("Glider", &[".O.", "..O", "OOO"], 4),
+    ("Lightweight spaceship", &[".O..O", "O....", "O...O", "OOOO."], 4),
+];
+
+/// Objects of the same kind found on the board.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CensusEntry {
+    name: String,
+    locations: Vec<(i32, i32)>,
+}
+
+impl CensusEntry {
+    /// Get the name of the object, or `Unknown (n cells)` if it is not known.
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+    /// Get the number of the objects.
+    pub fn get_count(&self) -> usize {
+        self.locations.len()
+    }
+    /// Get the top-left corners of the bounding boxes of the objects.
+    pub fn get_locations(&self) -> &[(i32, i32)] {
+        &self.locations
+    }
+}
+
+/// Census of the objects on the board, the most common object first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Census {
+    entries: Vec<CensusEntry>,
+}
+
+impl Census {
+    /// Take the census of the board.
+    /// The live cells are grouped with the cells within 2 cells in any of the following
+    /// `phases` generations, so that the parts of an object are counted together.
+    /// A group which is not known is separated into the groups of touching cells,
+    /// if all of them are known.
+    pub fn take<U: Universe>(universe: &U, phases: u32) -> Census {
+        let cells = universe.live_cells();
+        let all = all_phases(&cells, universe.get_rule(), phases);
+        let known = known_objects();
+        let mut objects = Vec::new();
+        for group in split_objects(&cells, &all, 2) {
+            if known.contains_key(&canonical(&group)) {
+                objects.push(group);
+                continue;
+            }
+            let parts = split_objects(&group, &all, 1);
+            if parts.len() > 1 && parts.iter().all(|part| known.contains_key(&canonical(part))) {
+                objects.extend(parts);
+            } else {
+                objects.push(group);
+            }
+        }
+        let mut entries: Vec<CensusEntry> = Vec::new();
+        for object in objects {
+            let bounds = BoundingBox::from_cells(object.iter().copied()).expect("object is not empty");
+            let name = match known.get(&canonical(&object)) {
+                Some(&index) => KNOWN_OBJECTS[index].0.to_string(),
+                None => format!("Unknown ({} cells)", object.len()),
+            };
+            match entries.iter_mut().find(|entry| entry.name == name) {
+                Some(entry) => entry.locations.push(bounds.get_min()),
+                None => entries.push(CensusEntry { name, locations: vec![bounds.get_min()] }),
+            }
+        }
+        for entry in &mut entries {
+            entry.locations.sort_by_key(|&(x, y)| (y, x));
+        }
+        entries.sort_by(|a, b| b.get_count().cmp(&a.get_count()).then_with(|| a.name.cmp(&b.name)));
+        Census { entries }
+    }
+    /// Get the kinds of the objects.
+    pub fn get_entries(&self) -> &[CensusEntry] {
+        &self.entries
+    }
+    /// Get the number of the objects of the name.
+    pub fn count(&self, name: &str) -> usize {
+        self.entries.iter().find(|entry| entry.name == name).map_or(0, |entry| entry.get_count())
+    }
+}
+
+/// Parse the rows of `O` and `.` into the cells.
+fn parse_rows(rows: &[&str]) -> Vec<(i32, i32)> {
+    let mut cells = Vec::new();
+    for (y, row) in rows.iter().enumerate() {
+        for (x, character) in row.chars().enumerate() {
+            if character == 'O' {
+                cells.push((x as i32, y as i32));
+            }
+        }
+    }
+    cells
+}
+
+/// Get the smallest form of the cells among the 8 transforms, regardless of the position.
+fn canonical(cells: &[(i32, i32)]) -> Vec<(i32, i32)> {
+    Transform::ALL
+        .iter()
+        .map(|transform| {
+            let mut cells = transform.apply_to_cells(cells);
+            cells.sort_unstable();
+            cells
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+/// Get the canonical forms of all the phases of the known objects.
+fn known_objects() -> &'static HashMap<Vec<(i32, i32)>, usize> {
+    static FORMS: OnceLock<HashMap<Vec<(i32, i32)>, usize>> = OnceLock::new();
+    FORMS.get_or_init(|| {
+        let mut forms = HashMap::new();
+        for (index, (_, rows, period)) in KNOWN_OBJECTS.iter().enumerate() {
+            let mut universe = SparseLife::from_cells(parse_rows(rows));
+            for _ in 0..*period {
+                forms.insert(canonical(&universe.live_cells()), index);
+                universe.next_generation();
+            }
+        }
+        forms
+    })
+}
+
+/// Get the alive cells of the following `phases` generations.
+fn all_phases(cells: &[(i32, i32)], rule: &Rule, phases: u32) -> HashSet<(i32, i32)> {
+    let mut all: HashSet<(i32, i32)> = cells.iter().copied().collect();
+    let mut universe = SparseLife::from_cells(cells.iter().copied());
+    if universe.set_rule(*rule).is_ok() {
+        for _ in 1..phases {
+            universe.next_generation();
+            all.extend(universe.live_cells());
+        }
+    }
+    all
+}
+
+/// Split the cells into the objects, grouping the cells within `reach` cells,
+/// through the cells of all the phases.
+fn split_objects(cells: &[(i32, i32)], all: &HashSet<(i32, i32)>, reach: i32) -> Vec<Vec<(i32, i32)>> {
+    let alive: HashSet<(i32, i32)> = cells.iter().copied().collect();
+    let mut visited: HashSet<(i32, i32)> = HashSet::new();
+    let mut objects = Vec::new();
+    for &start in cells {
+        if visited.contains(&start) {
+            continue;
+        }
+        let mut object = Vec::new();
+        let mut queue = VecDeque::from([start]);
+        visited.insert(start);
+        while let Some((x, y)) = queue.pop_front() {
+            if alive.contains(&(x, y)) {
+                object.push((x, y));
+            }
+            for dy in -reach..=reach {
+                for dx in -reach..=reach {
+                    let neighbor = (x + dx, y + dy);
+                    if all.contains(&neighbor) && visited.insert(neighbor) {
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+        }
+        objects.push(object);
+    }
+    objects
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Take the census of the cells moved to the position, over the period of the object.
+    fn census_of(cells: &[(i32, i32)], (left, top): (i32, i32), phases: u32) -> Census {
+        Census::take(&SparseLife::from_cells(cells.iter().map(|&(x, y)| (x + left, y + top))), phases)
+    }
+
+    #[test]
+    fn names_the_known_objects_in_every_orientation() {
+        for (name, rows, period) in KNOWN_OBJECTS {
+            let cells = parse_rows(rows);
+            for transform in Transform::ALL {
+                let census = census_of(&transform.apply_to_cells(&cells), (-7, 11), period);
+                assert_eq!(census.get_entries().len(), 1, "{} {:?}: {:?}", name, transform, census);
+                assert_eq!(census.count(name), 1, "{} {:?}: {:?}", name, transform, census);
+                assert_eq!(census.get_entries()[0].get_locations(), [(-7, 11)]);
+            }
+        }
+    }
+
+    #[test]
+    fn names_every_phase_of_the_glider() {
+        let mut glider = SparseLife::from_cells(parse_rows(&[".O.", "..O", "OOO"]));
+        for transform in Transform::ALL {
+            for _ in 0..4 {
+                glider.next_generation();
+                let census = census_of(&transform.apply_to_cells(&glider.live_cells()), (0, 0), 4);
+                assert_eq!(census.count("Glider"), 1, "{:?} {:?}", transform, census);
+            }
+        }
+    }
+
+    #[test]
+    fn counts_the_objects_regardless_of_their_orientations() {
+        let boat = parse_rows(&["OO.", "O.O", ".O."]);
+        let cells: Vec<(i32, i32)> = Transform::ALL
+            .iter()
+            .enumerate()
+            .flat_map(|(index, transform)| {
+                let left = index as i32 * 10;
+                transform.apply_to_cells(&boat).into_iter().map(move |(x, y)| (x + left, y))
+            })
+            .collect();
+        let census = Census::take(&SparseLife::from_cells(cells), 1);
+        assert_eq!(census.get_entries().len(), 1, "{:?}", census);
+        assert_eq!(census.count("Boat"), 8);
+    }
+
+    #[test]
+    fn names_the_unknown_objects_by_their_size() {
+        let census = census_of(&[(0, 0), (1, 0), (2, 0), (0, 1)], (0, 0), 1);
+        assert_eq!(census.count("Unknown (4 cells)"), 1, "{:?}", census);
+        assert!(Census::take(&SparseLife::new(), 1).get_entries().is_empty());
+    }
+}
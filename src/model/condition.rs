@@ -0,0 +1,112 @@
+/// condition.rs
+/// - Conditions to run the generations until.
+use std::fmt;
+
+use super::{CycleDetector, Universe};
+
+/// The number of generations the population has to stay the same by default.
+const DEFAULT_STABLE_GENERATIONS: u64 = 10;
+
+/// Condition which stops a run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RunCondition {
+    /// The population equals the number.
+    Population(u64),
+    /// The population stayed the same for the number of generations.
+    PopulationStable(u64),
+    /// The board became a still life, an oscillator or a spaceship.
+    #[default]
+    Period,
+    /// The generation reached the number.
+    Generation(u64),
+}
+
+impl RunCondition {
+    /// All the kinds of the conditions with their default values.
+    pub const ALL: [RunCondition; 4] = [
+        RunCondition::Population(0),
+        RunCondition::PopulationStable(DEFAULT_STABLE_GENERATIONS),
+        RunCondition::Period,
+        RunCondition::Generation(0),
+    ];
+
+    /// Get the name of the kind of the condition.
+    pub fn name(&self) -> &'static str {
+        match self {
+            RunCondition::Population(_) => "Population equals",
+            RunCondition::PopulationStable(_) => "Population stable for",
+            RunCondition::Period => "Period detected",
+            RunCondition::Generation(_) => "Generation reached",
+        }
+    }
+    /// Get the number of the condition, if it has one.
+    pub fn get_value(&self) -> Option<u64> {
+        match self {
+            RunCondition::Population(value)
+            | RunCondition::PopulationStable(value)
+            | RunCondition::Generation(value) => Some(*value),
+            RunCondition::Period => None,
+        }
+    }
+    /// Get the condition of the same kind with the number.
+    pub fn with_value(&self, value: u64) -> RunCondition {
+        match self {
+            RunCondition::Population(_) => RunCondition::Population(value),
+            RunCondition::PopulationStable(_) => RunCondition::PopulationStable(value),
+            RunCondition::Period => RunCondition::Period,
+            RunCondition::Generation(_) => RunCondition::Generation(value),
+        }
+    }
+}
+
+impl fmt::Display for RunCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunCondition::Population(population) => write!(f, "population {}", population),
+            RunCondition::PopulationStable(generations) => {
+                write!(f, "population stable for {} generations", generations)
+            }
+            RunCondition::Period => write!(f, "period detected"),
+            RunCondition::Generation(generation) => write!(f, "generation {}", generation),
+        }
+    }
+}
+
+/// Watcher of a run which checks the condition on each observed generation.
+#[derive(Debug, Clone)]
+pub struct RunUntil {
+    condition: RunCondition,
+    population: Option<usize>,
+    /// The generation since when the population stayed the same.
+    stable_since: u64,
+    detector: CycleDetector,
+}
+
+impl RunUntil {
+    /// Create a new watcher of the condition.
+    pub fn new(condition: RunCondition) -> RunUntil {
+        RunUntil { condition, population: None, stable_since: 0, detector: CycleDetector::default() }
+    }
+    /// Get the condition.
+    pub fn get_condition(&self) -> RunCondition {
+        self.condition
+    }
+    /// Observe the board, and check if the condition is met.
+    pub fn check<U: Universe>(&mut self, universe: &U) -> bool {
+        let generation = universe.get_generation();
+        match self.condition {
+            RunCondition::Population(population) => universe.population() as u64 == population,
+            RunCondition::PopulationStable(generations) => {
+                let population = universe.population();
+                // Start over if the population changed or the generation went backwards.
+                if self.population != Some(population) || generation < self.stable_since {
+                    self.population = Some(population);
+                    self.stable_since = generation;
+                }
+                generation.saturating_sub(self.stable_since) >= generations
+            }
+            RunCondition::Period => self.detector.observe(universe).is_some(),
+            RunCondition::Generation(target) => generation >= target,
+        }
+    }
+}
@@ -0,0 +1,177 @@
+/// cycle.rs
+/// - Detection of still lifes, oscillators and spaceships by hashing the board states.
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use super::Universe;
+
+/// The number of generations kept to find a cycle by default.
+const DEFAULT_MAX_PERIOD: usize = 1000;
+/// The largest number of cells kept over all the generations, to bound the memory.
+const MAX_KEPT_CELLS: usize = 1 << 22;
+
+/// Cycle which the board settled into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cycle {
+    /// The board does not change.
+    StillLife,
+    /// The board repeats itself in place after `period` generations.
+    Oscillator { period: u64 },
+    /// The board repeats itself after `period` generations, moved by `(dx, dy)` cells.
+    Spaceship { period: u64, dx: i32, dy: i32 },
+}
+
+impl Cycle {
+    /// Get the number of generations of the cycle.
+    pub fn get_period(&self) -> u64 {
+        match self {
+            Cycle::StillLife => 1,
+            Cycle::Oscillator { period } | Cycle::Spaceship { period, .. } => *period,
+        }
+    }
+}
+
+impl fmt::Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Cycle::StillLife => write!(f, "still life"),
+            Cycle::Oscillator { period } => write!(f, "oscillator with period {}", period),
+            Cycle::Spaceship { period, dx, dy } => {
+                write!(f, "spaceship with period {} and displacement ({}, {})", period, dx, dy)
+            }
+        }
+    }
+}
+
+/// Board state seen at a generation: the cells relative to their bounding box with their hash,
+/// and the top-left corner of the bounding box.
+#[derive(Debug, Clone)]
+struct State {
+    hash: u64,
+    cells: Vec<(i32, i32)>,
+    generation: u64,
+    origin: (i32, i32),
+}
+
+/// Detector of the cycles, fed with the board after each step.
+/// The boards are compared regardless of their positions, so that the spaceships are found.
+/// Only consecutive generations are compared, so nothing is found while generations are skipped.
+#[derive(Debug, Clone)]
+pub struct CycleDetector {
+    /// The states of consecutive generations, from the oldest.
+    states: VecDeque<State>,
+    /// The latest generation of each hash.
+    latest: HashMap<u64, u64>,
+    /// The number of cells of the kept states.
+    cell_count: usize,
+    max_period: usize,
+}
+
+impl Default for CycleDetector {
+    fn default() -> CycleDetector {
+        CycleDetector::new(DEFAULT_MAX_PERIOD)
+    }
+}
+
+impl CycleDetector {
+    /// Create a new detector which finds the cycles of up to `max_period` generations.
+    /// Fewer generations are kept if their cells exceed the memory budget.
+    pub fn new(max_period: usize) -> CycleDetector {
+        CycleDetector { states: VecDeque::new(), latest: HashMap::new(), cell_count: 0, max_period }
+    }
+    /// Forget the observed boards, e.g. after the board was edited.
+    pub fn reset(&mut self) {
+        self.states.clear();
+        self.latest.clear();
+        self.cell_count = 0;
+    }
+    /// Observe the board, and get the cycle if the board was seen before.
+    /// An empty board has no cycle. The detector is reset unless the generation
+    /// follows the previous one, e.g. if it went backwards or HashLife skipped generations.
+    pub fn observe<U: Universe>(&mut self, universe: &U) -> Option<Cycle> {
+        let generation = universe.get_generation();
+        if self.states.back().is_some_and(|state| state.generation.checked_add(1) != Some(generation)) {
+            self.reset();
+        }
+        let Some(bounds) = universe.bounding_box() else {
+            self.reset();
+            return None;
+        };
+        let (left, top) = bounds.get_min();
+        let mut cells: Vec<(i32, i32)> =
+            universe.live_cells().into_iter().map(|(x, y)| (x - left, y - top)).collect();
+        cells.sort_unstable();
+        let mut hasher = DefaultHasher::new();
+        cells.hash(&mut hasher);
+        let state = State { hash: hasher.finish(), cells, generation, origin: (left, top) };
+
+        let cycle = self.find_seen(&state).map(|seen| {
+            let period = generation - seen.generation;
+            let (dx, dy) = (left - seen.origin.0, top - seen.origin.1);
+            match (period, dx, dy) {
+                (1, 0, 0) => Cycle::StillLife,
+                (period, 0, 0) => Cycle::Oscillator { period },
+                (period, dx, dy) => Cycle::Spaceship { period, dx, dy },
+            }
+        });
+        self.latest.insert(state.hash, generation);
+        self.cell_count += state.cells.len();
+        self.states.push_back(state);
+        while self.states.len() > self.max_period.max(1)
+            || (self.states.len() > 1 && self.cell_count > MAX_KEPT_CELLS)
+        {
+            let Some(oldest) = self.states.pop_front() else {
+                break;
+            };
+            self.cell_count -= oldest.cells.len();
+            if self.latest.get(&oldest.hash) == Some(&oldest.generation) {
+                self.latest.remove(&oldest.hash);
+            }
+        }
+        cycle
+    }
+    /// Get the latest kept state with the same cells as the state.
+    fn find_seen(&self, state: &State) -> Option<&State> {
+        let generation = *self.latest.get(&state.hash)?;
+        let first = self.states.front()?.generation;
+        let seen = self.states.get((generation - first) as usize)?;
+        (seen.cells == state.cells).then_some(seen)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{HashLife, SparseLife};
+
+    /// Step the universe until a cycle is found, for up to `generations` generations.
+    fn find_cycle<U: Universe>(universe: &mut U, generations: usize) -> Option<Cycle> {
+        let mut detector = CycleDetector::default();
+        detector.observe(universe);
+        (0..generations).find_map(|_| {
+            universe.next_generation();
+            detector.observe(universe)
+        })
+    }
+
+    #[test]
+    fn finds_cycles() {
+        let mut block = SparseLife::from_cells([(0, 0), (1, 0), (0, 1), (1, 1)]);
+        assert_eq!(find_cycle(&mut block, 10), Some(Cycle::StillLife));
+        let mut blinker = SparseLife::from_cells([(0, 1), (1, 1), (2, 1)]);
+        assert_eq!(find_cycle(&mut blinker, 10), Some(Cycle::Oscillator { period: 2 }));
+        let mut glider = SparseLife::from_cells([(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]);
+        assert_eq!(find_cycle(&mut glider, 10), Some(Cycle::Spaceship { period: 4, dx: 1, dy: 1 }));
+    }
+
+    #[test]
+    fn ignores_skipped_generations() {
+        let mut detector = CycleDetector::default();
+        let mut blinker = HashLife::from_cells([(0, 1), (1, 1), (2, 1)]);
+        for _ in 0..10 {
+            blinker.step_pow2(1);
+            assert_eq!(detector.observe(&blinker), None);
+        }
+    }
+}
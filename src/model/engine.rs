@@ -0,0 +1,128 @@
+/// engine.rs
+/// - Engines to compute the generations, and the topologies they run on.
+use super::Topology;
+
+/// Engine to compute the generations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Engine {
+    /// Cell by cell on the board.
+    #[default]
+    Grid,
+    /// Only the alive cells on the unbounded plane.
+    Sparse,
+    /// HashLife on the unbounded plane, advancing `2^k` generations per step.
+    HashLife,
+}
+
+impl Engine {
+    /// All the engines.
+    pub const ALL: [Engine; 3] = [Engine::Grid, Engine::Sparse, Engine::HashLife];
+
+    /// Get the name of the engine.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Engine::Grid => "Grid",
+            Engine::Sparse => "Sparse",
+            Engine::HashLife => "HashLife",
+        }
+    }
+    /// Get the engine from its name, ignoring the case.
+    pub fn from_name(name: &str) -> Option<Engine> {
+        let name = name.trim().to_lowercase();
+        Engine::ALL.into_iter().find(|engine| engine.name().to_lowercase() == name)
+    }
+    /// Check if the engine computes the same generations as the board with the topology.
+    /// The unbounded engines do not wrap the edges, so they run only on the plane.
+    pub fn supports(&self, topology: Topology) -> bool {
+        match self {
+            Engine::Grid => true,
+            Engine::Sparse | Engine::HashLife => topology == Topology::Plane,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{HashLife, LifeGame, SparseLife, Universe};
+
+    /// Step a row across the whole width of a small board once with the engine,
+    /// and get the alive cells on the board.
+    fn step_row(engine: Engine, topology: Topology) -> Vec<(i32, i32)> {
+        let row: Vec<(i32, i32)> = (0..16).map(|x| (x, 8)).collect();
+        let mut life_game = LifeGame::new(16, 16);
+        life_game.set_topology(topology);
+        let mut cells = match engine {
+            Engine::Grid => {
+                for &(x, y) in &row {
+                    life_game.set_alive(x, y, true);
+                }
+                life_game.next_generation();
+                life_game.live_cells()
+            }
+            Engine::Sparse => {
+                let mut sparse_life = SparseLife::from_cells(row);
+                sparse_life.next_generation();
+                sparse_life.live_cells().into_iter().filter(|&(x, y)| life_game.get_index(x, y).is_some()).collect()
+            }
+            Engine::HashLife => {
+                let mut hash_life = HashLife::from_cells(row);
+                hash_life.next_generation();
+                hash_life.live_cells().into_iter().filter(|&(x, y)| life_game.get_index(x, y).is_some()).collect()
+            }
+        };
+        cells.sort_unstable();
+        cells
+    }
+
+    #[test]
+    fn supports_only_the_matching_topologies() {
+        for topology in Topology::ALL {
+            assert!(Engine::Grid.supports(topology));
+            for engine in [Engine::Sparse, Engine::HashLife] {
+                assert_eq!(engine.supports(topology), topology == Topology::Plane, "{:?} {:?}", engine, topology);
+            }
+        }
+    }
+
+    #[test]
+    fn unsupported_topologies_give_other_generations() {
+        for topology in Topology::ALL {
+            for engine in [Engine::Sparse, Engine::HashLife] {
+                let same = step_row(Engine::Grid, topology) == step_row(engine, topology);
+                assert_eq!(same, engine.supports(topology), "{:?} {:?}", engine, topology);
+            }
+        }
+    }
+
+    #[test]
+    fn sparse_life_matches_life_game_on_plane() {
+        // The R-pentomino in the middle of a board large enough to keep it for the generations.
+        let r_pentomino = [(1, 0), (2, 0), (0, 1), (1, 1), (1, 2)].map(|(x, y)| (x + 60, y + 60));
+        let mut life_game = LifeGame::new(128, 128);
+        life_game.set_topology(Topology::Plane);
+        for (x, y) in r_pentomino {
+            life_game.set_alive(x, y, true);
+        }
+        let mut sparse_life = SparseLife::from_cells(r_pentomino);
+        for _ in 0..50 {
+            life_game.next_generation();
+            sparse_life.next_generation();
+            let mut expected = life_game.live_cells();
+            let mut cells = sparse_life.live_cells();
+            expected.sort_unstable();
+            cells.sort_unstable();
+            assert_eq!(cells, expected, "generation {}", life_game.get_generation());
+        }
+        assert_eq!(sparse_life.get_generation(), life_game.get_generation());
+    }
+
+    #[test]
+    fn parses_the_names() {
+        for engine in Engine::ALL {
+            assert_eq!(Engine::from_name(engine.name()), Some(engine));
+        }
+        assert_eq!(Engine::from_name(" hashlife "), Some(Engine::HashLife));
+        assert_eq!(Engine::from_name("quadtree"), None);
+    }
+}
@@ -0,0 +1,621 @@
+/// hashlife.rs
+/// - Quadtree life-game with memoized canonical nodes (HashLife).
+use std::collections::HashMap;
+
+use super::{BoundingBox, Rule, RuleError, Universe};
+
+type NodeId = u32;
+
+/// Dead leaf.
+const DEAD: NodeId = 0;
+/// Alive leaf.
+const ALIVE: NodeId = 1;
+/// The smallest level of the root.
+const MIN_ROOT_LEVEL: u8 = 3;
+/// The largest level of the root, so that the coordinates fit in i64.
+const MAX_ROOT_LEVEL: u8 = 62;
+/// The largest `k` of a single step of `2^k` generations, so that the root fits in `MAX_ROOT_LEVEL`.
+const MAX_STEP: u8 = MAX_ROOT_LEVEL - 3;
+/// The number of nodes to keep before collecting the garbage.
+const GC_THRESHOLD: usize = 1 << 22;
+
+/// Quadtree node. The leaves (level 0) are single cells
+/// and a node of level `n` covers `2^n` x `2^n` cells.
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    level: u8,
+    nw: NodeId,
+    ne: NodeId,
+    sw: NodeId,
+    se: NodeId,
+    population: u64,
+}
+
+/// Node of a quadtree imported into or exported from HashLife, as in the macrocell format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeNode {
+    /// 8 x 8 cells (level 3), one byte per row with the left cell in the lowest bit.
+    Leaf([u8; 8]),
+    /// Node of level 4 or more with the nw, ne, sw and se children.
+    /// A child `0` is empty and a child `n` is the `n`th node of the tree.
+    Branch { level: u8, children: [usize; 4] },
+}
+
+/// HashLife model
+/// The universe is a plane centered at the origin, which grows up to `2^62` x `2^62` cells.
+/// The cells which move beyond it are dropped.
+#[derive(Debug, Clone)]
+pub struct HashLife {
+    nodes: Vec<Node>,
+    canonical: HashMap<[NodeId; 4], NodeId>,
+    results: HashMap<(NodeId, u8), NodeId>,
+    empty: Vec<NodeId>,
+    root: NodeId,
+    generation: u64,
+    keep_alive: bool,
+    rule: Rule,
+}
+
+impl Default for HashLife {
+    fn default() -> HashLife {
+        HashLife::new()
+    }
+}
+
+impl HashLife {
+    /// Create a new empty universe.
+    pub fn new() -> HashLife {
+        let leaf = |population| Node { level: 0, nw: DEAD, ne: DEAD, sw: DEAD, se: DEAD, population };
+        let mut hash_life = HashLife {
+            nodes: vec![leaf(0), leaf(1)],
+            canonical: HashMap::new(),
+            results: HashMap::new(),
+            empty: vec![DEAD],
+            root: DEAD,
+            generation: 0,
+            keep_alive: false,
+            rule: Rule::default(),
+        };
+        hash_life.root = hash_life.empty(MIN_ROOT_LEVEL);
+        hash_life
+    }
+    /// Create a new universe with the alive cells.
+    pub fn from_cells<I: IntoIterator<Item = (i32, i32)>>(cells: I) -> HashLife {
+        let mut hash_life = HashLife::new();
+        for (x, y) in cells {
+            hash_life.set_alive(x, y, true);
+        }
+        hash_life.keep_alive = hash_life.population() > 0;
+        hash_life
+    }
+    /// Create a new universe from the nodes of a quadtree. The last node is the root.
+    /// If a node is malformed, its index is returned as the error.
+    pub fn from_tree(tree: &[TreeNode]) -> Result<HashLife, usize> {
+        let mut hash_life = HashLife::new();
+        let mut ids: Vec<NodeId> = Vec::with_capacity(tree.len());
+        for (index, node) in tree.iter().enumerate() {
+            let id = match *node {
+                TreeNode::Leaf(rows) => hash_life.build_leaf(&rows, 0, 0, 3),
+                TreeNode::Branch { level, children } => {
+                    if !(4..=MAX_ROOT_LEVEL).contains(&level) {
+                        return Err(index);
+                    }
+                    let mut child_ids = [DEAD; 4];
+                    for (id, child) in child_ids.iter_mut().zip(children) {
+                        *id = match child {
+                            0 => hash_life.empty(level - 1),
+                            child if child <= index && hash_life.level(ids[child - 1]) == level - 1 => ids[child - 1],
+                            _ => return Err(index),
+                        };
+                    }
+                    hash_life.join(child_ids[0], child_ids[1], child_ids[2], child_ids[3])
+                }
+            };
+            ids.push(id);
+        }
+        if let Some(&root) = ids.last() {
+            hash_life.root = root;
+        }
+        hash_life.keep_alive = hash_life.population() > 0;
+        Ok(hash_life)
+    }
+    /// Get the nodes of the quadtree, children first and the root last.
+    /// Empty nodes are omitted, so the tree of an empty universe has no nodes.
+    pub fn to_tree(&self) -> Vec<TreeNode> {
+        let mut tree = Vec::new();
+        self.export(self.root, &mut tree, &mut HashMap::new());
+        tree
+    }
+    /// Set the rule used by the following generations.
+    /// Rules with B0 would fill the infinite plane, so they are rejected.
+    pub fn set_rule(&mut self, rule: Rule) -> Result<(), RuleError> {
+        if rule.is_birth(0) {
+            return Err(RuleError::UnsupportedB0);
+        }
+        if rule != self.rule {
+            self.rule = rule;
+            self.results.clear();
+        }
+        Ok(())
+    }
+    /// Set the generation counter.
+    pub fn set_generation(&mut self, generation: u64) {
+        self.generation = generation;
+    }
+    /// Get the number of nodes kept in memory.
+    pub fn get_node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Advance the universe by `2^k` generations at once.
+    /// `k` above 59 is run as `2^(k - 59)` steps of `2^59` generations.
+    /// The generation counter wraps around after `u64::MAX`.
+    /// The cells which would leave the largest universe are dropped.
+    ///
+    /// # Panics
+    /// Panics if `k` is 64 or more, as the generations do not fit in the counter.
+    pub fn step_pow2(&mut self, k: u8) {
+        assert!(k < 64, "cannot step 2^{} generations", k);
+        if k > MAX_STEP {
+            for _ in 0..1u32 << (k - MAX_STEP) {
+                self.step_pow2(MAX_STEP);
+            }
+            return;
+        }
+        while self.level(self.root) < k + 3 || (self.level(self.root) < MAX_ROOT_LEVEL && !self.is_padded()) {
+            self.expand();
+        }
+        self.root = self.successor(self.root, k);
+        self.generation = self.generation.wrapping_add(1 << k);
+        self.keep_alive = self.population() > 0;
+        if self.nodes.len() > GC_THRESHOLD {
+            self.collect_garbage();
+        }
+    }
+    /// Advance the universe by `generations` generations.
+    pub fn step(&mut self, generations: u64) {
+        for k in 0..64 {
+            if generations & (1 << k) != 0 {
+                self.step_pow2(k);
+            }
+        }
+    }
+
+    fn level(&self, node: NodeId) -> u8 {
+        self.nodes[node as usize].level
+    }
+    fn node(&self, node: NodeId) -> Node {
+        self.nodes[node as usize]
+    }
+    /// Get the canonical node of the children.
+    fn join(&mut self, nw: NodeId, ne: NodeId, sw: NodeId, se: NodeId) -> NodeId {
+        if let Some(&node) = self.canonical.get(&[nw, ne, sw, se]) {
+            return node;
+        }
+        let population = [nw, ne, sw, se].iter().map(|&child| self.node(child).population).sum();
+        let id = self.nodes.len() as NodeId;
+        self.nodes.push(Node { level: self.level(nw) + 1, nw, ne, sw, se, population });
+        self.canonical.insert([nw, ne, sw, se], id);
+        id
+    }
+    /// Get the empty node of the level.
+    fn empty(&mut self, level: u8) -> NodeId {
+        while self.empty.len() <= level as usize {
+            let child = *self.empty.last().unwrap();
+            let node = self.join(child, child, child, child);
+            self.empty.push(node);
+        }
+        self.empty[level as usize]
+    }
+    /// Get the center node of one level lower.
+    fn centered(&mut self, node: NodeId) -> NodeId {
+        let Node { nw, ne, sw, se, .. } = self.node(node);
+        self.join(self.node(nw).se, self.node(ne).sw, self.node(sw).ne, self.node(se).nw)
+    }
+    /// Surround the root with empty nodes, keeping the center.
+    /// The root is not expanded beyond `MAX_ROOT_LEVEL`, so that the coordinates fit in i64.
+    fn expand(&mut self) {
+        let Node { level, nw, ne, sw, se, .. } = self.node(self.root);
+        if level >= MAX_ROOT_LEVEL {
+            return;
+        }
+        let empty = self.empty(level - 1);
+        let nw = self.join(empty, empty, empty, nw);
+        let ne = self.join(empty, empty, ne, empty);
+        let sw = self.join(empty, sw, empty, empty);
+        let se = self.join(se, empty, empty, empty);
+        self.root = self.join(nw, ne, sw, se);
+    }
+    /// Check if all the alive cells are in the center quarter of the root.
+    fn is_padded(&mut self) -> bool {
+        let center = self.centered(self.root);
+        let center = self.centered(center);
+        self.node(center).population == self.node(self.root).population
+    }
+    /// Get the center of a level 2 node after one generation.
+    fn step_leaf(&mut self, node: NodeId) -> NodeId {
+        let mut cells = [[false; 4]; 4];
+        for (y, row) in cells.iter_mut().enumerate() {
+            for (x, cell) in row.iter_mut().enumerate() {
+                *cell = self.get(node, x as i64, y as i64);
+            }
+        }
+        let mut next = [DEAD; 4];
+        for (i, (x, y)) in [(1, 1), (2, 1), (1, 2), (2, 2)].into_iter().enumerate() {
+            let mut count = 0;
+            for dy in 0..3 {
+                for dx in 0..3 {
+                    if (dx != 1 || dy != 1) && cells[y + dy - 1][x + dx - 1] {
+                        count += 1;
+                    }
+                }
+            }
+            if self.rule.next_state(cells[y][x], count) {
+                next[i] = ALIVE;
+            }
+        }
+        self.join(next[0], next[1], next[2], next[3])
+    }
+    /// Get the center of the node, one level lower, after `2^j` generations.
+    /// `j` must be `level - 2` or less.
+    fn successor(&mut self, node: NodeId, j: u8) -> NodeId {
+        let Node { level, nw, ne, sw, se, population } = self.node(node);
+        if population == 0 {
+            return self.empty(level - 1);
+        }
+        if let Some(&result) = self.results.get(&(node, j)) {
+            return result;
+        }
+        let result = if level == 2 {
+            self.step_leaf(node)
+        } else {
+            let (nw_, ne_, sw_, se_) = (self.node(nw), self.node(ne), self.node(sw), self.node(se));
+            let n01 = self.join(nw_.ne, ne_.nw, nw_.se, ne_.sw);
+            let n10 = self.join(nw_.sw, nw_.se, sw_.nw, sw_.ne);
+            let n11 = self.join(nw_.se, ne_.sw, sw_.ne, se_.nw);
+            let n12 = self.join(ne_.sw, ne_.se, se_.nw, se_.ne);
+            let n21 = self.join(sw_.ne, se_.nw, sw_.se, se_.sw);
+            let parts = [nw, n01, ne, n10, n11, n12, sw, n21, se];
+            let full_speed = j == level - 2;
+            let mut r = [DEAD; 9];
+            for (result, part) in r.iter_mut().zip(parts) {
+                *result = if full_speed { self.successor(part, j - 1) } else { self.centered(part) };
+            }
+            let next_j = if full_speed { j - 1 } else { j };
+            let quarters = [
+                self.join(r[0], r[1], r[3], r[4]),
+                self.join(r[1], r[2], r[4], r[5]),
+                self.join(r[3], r[4], r[6], r[7]),
+                self.join(r[4], r[5], r[7], r[8]),
+            ];
+            let mut q = [DEAD; 4];
+            for (result, quarter) in q.iter_mut().zip(quarters) {
+                *result = self.successor(quarter, next_j);
+            }
+            self.join(q[0], q[1], q[2], q[3])
+        };
+        self.results.insert((node, j), result);
+        result
+    }
+
+    /// Get the offset of the root from the origin.
+    fn origin(&self) -> i64 {
+        -(1 << (self.level(self.root) - 1))
+    }
+    /// Check if the cell at the position relative to the node is alive.
+    fn get(&self, node: NodeId, x: i64, y: i64) -> bool {
+        let node = self.node(node);
+        if node.population == 0 {
+            return false;
+        }
+        if node.level == 0 {
+            return true;
+        }
+        let half = 1 << (node.level - 1);
+        match (x < half, y < half) {
+            (true, true) => self.get(node.nw, x, y),
+            (false, true) => self.get(node.ne, x - half, y),
+            (true, false) => self.get(node.sw, x, y - half),
+            (false, false) => self.get(node.se, x - half, y - half),
+        }
+    }
+    /// Set the cell at the position relative to the node, and get the new node.
+    fn set(&mut self, node: NodeId, x: i64, y: i64, alive: bool) -> NodeId {
+        let Node { level, nw, ne, sw, se, .. } = self.node(node);
+        if level == 0 {
+            return if alive { ALIVE } else { DEAD };
+        }
+        let half = 1 << (level - 1);
+        match (x < half, y < half) {
+            (true, true) => {
+                let nw = self.set(nw, x, y, alive);
+                self.join(nw, ne, sw, se)
+            }
+            (false, true) => {
+                let ne = self.set(ne, x - half, y, alive);
+                self.join(nw, ne, sw, se)
+            }
+            (true, false) => {
+                let sw = self.set(sw, x, y - half, alive);
+                self.join(nw, ne, sw, se)
+            }
+            (false, false) => {
+                let se = self.set(se, x - half, y - half, alive);
+                self.join(nw, ne, sw, se)
+            }
+        }
+    }
+    /// Build the node of the level from the cells of the leaf rows at the offset.
+    fn build_leaf(&mut self, rows: &[u8; 8], x: usize, y: usize, level: u8) -> NodeId {
+        if level == 0 {
+            return if rows[y] & (1 << x) != 0 { ALIVE } else { DEAD };
+        }
+        let half = 1 << (level - 1);
+        let nw = self.build_leaf(rows, x, y, level - 1);
+        let ne = self.build_leaf(rows, x + half, y, level - 1);
+        let sw = self.build_leaf(rows, x, y + half, level - 1);
+        let se = self.build_leaf(rows, x + half, y + half, level - 1);
+        self.join(nw, ne, sw, se)
+    }
+    /// Add the node and its children to the tree, and get its index in the tree.
+    fn export(&self, node: NodeId, tree: &mut Vec<TreeNode>, indices: &mut HashMap<NodeId, usize>) -> usize {
+        let Node { level, nw, ne, sw, se, population } = self.node(node);
+        if population == 0 {
+            return 0;
+        }
+        if let Some(&index) = indices.get(&node) {
+            return index;
+        }
+        let tree_node = if level == 3 {
+            let mut rows = [0u8; 8];
+            for (y, row) in rows.iter_mut().enumerate() {
+                for x in 0..8 {
+                    if self.get(node, x, y as i64) {
+                        *row |= 1 << x;
+                    }
+                }
+            }
+            TreeNode::Leaf(rows)
+        } else {
+            let children = [nw, ne, sw, se].map(|child| self.export(child, tree, indices));
+            TreeNode::Branch { level, children }
+        };
+        tree.push(tree_node);
+        indices.insert(node, tree.len());
+        tree.len()
+    }
+    /// Collect the alive cells of the node at the offset, skipping the cells outside the i32 range.
+    fn collect(&self, node: NodeId, x: i64, y: i64, cells: &mut Vec<(i32, i32)>) {
+        let node = self.node(node);
+        let size = 1i64 << node.level;
+        let range = i32::MIN as i64 - size + 1..=i32::MAX as i64;
+        if node.population == 0 || !range.contains(&x) || !range.contains(&y) {
+            return;
+        }
+        if node.level == 0 {
+            cells.push((x as i32, y as i32));
+            return;
+        }
+        let half = 1 << (node.level - 1);
+        self.collect(node.nw, x, y, cells);
+        self.collect(node.ne, x + half, y, cells);
+        self.collect(node.sw, x, y + half, cells);
+        self.collect(node.se, x + half, y + half, cells);
+    }
+    /// Get the smallest (or the largest, if `far` is set) x or y offset
+    /// of the alive cells in the node.
+    fn edge(&self, node: NodeId, horizontal: bool, far: bool, memo: &mut HashMap<NodeId, Option<i64>>) -> Option<i64> {
+        let Node { level, nw, ne, sw, se, population } = self.node(node);
+        if population == 0 {
+            return None;
+        }
+        if level == 0 {
+            return Some(0);
+        }
+        if let Some(&offset) = memo.get(&node) {
+            return offset;
+        }
+        let half = 1 << (level - 1);
+        let (low, high) = if horizontal { ([nw, sw], [ne, se]) } else { ([nw, ne], [sw, se]) };
+        let (first, second, first_offset, second_offset) =
+            if far { (high, low, half, 0) } else { (low, high, 0, half) };
+        let pick = |a: Option<i64>, b: Option<i64>| match (a, b) {
+            (Some(a), Some(b)) => Some(if far { a.max(b) } else { a.min(b) }),
+            (a, b) => a.or(b),
+        };
+        let offset = first
+            .iter()
+            .fold(None, |offset, &child| pick(offset, self.edge(child, horizontal, far, memo)))
+            .map(|offset| offset + first_offset)
+            .or_else(|| {
+                second
+                    .iter()
+                    .fold(None, |offset, &child| pick(offset, self.edge(child, horizontal, far, memo)))
+                    .map(|offset| offset + second_offset)
+            });
+        memo.insert(node, offset);
+        offset
+    }
+    /// Drop the nodes which are not reachable from the root.
+    pub fn collect_garbage(&mut self) {
+        let mut fresh = HashLife::new();
+        fresh.rule = self.rule;
+        fresh.generation = self.generation;
+        fresh.keep_alive = self.keep_alive;
+        let mut copied = HashMap::new();
+        fresh.root = fresh.copy_from(self, self.root, &mut copied);
+        *self = fresh;
+    }
+    fn copy_from(&mut self, other: &HashLife, node: NodeId, copied: &mut HashMap<NodeId, NodeId>) -> NodeId {
+        if node == DEAD || node == ALIVE {
+            return node;
+        }
+        if let Some(&id) = copied.get(&node) {
+            return id;
+        }
+        let Node { nw, ne, sw, se, .. } = other.node(node);
+        let children = [nw, ne, sw, se].map(|child| self.copy_from(other, child, copied));
+        let id = self.join(children[0], children[1], children[2], children[3]);
+        copied.insert(node, id);
+        id
+    }
+}
+
+impl Universe for HashLife {
+    fn get_generation(&self) -> u64 {
+        self.generation
+    }
+    fn get_rule(&self) -> &Rule {
+        &self.rule
+    }
+    fn is_alive(&self, x: i32, y: i32) -> bool {
+        let size = 1i64 << self.level(self.root);
+        let (x, y) = (x as i64 - self.origin(), y as i64 - self.origin());
+        (0..size).contains(&x) && (0..size).contains(&y) && self.get(self.root, x, y)
+    }
+    fn set_alive(&mut self, x: i32, y: i32, alive: bool) {
+        loop {
+            let size = 1i64 << self.level(self.root);
+            let (rx, ry) = (x as i64 - self.origin(), y as i64 - self.origin());
+            if (0..size).contains(&rx) && (0..size).contains(&ry) {
+                self.root = self.set(self.root, rx, ry, alive);
+                return;
+            }
+            if !alive {
+                return;
+            }
+            self.expand();
+        }
+    }
+    fn next_generation(&mut self) {
+        self.step_pow2(0);
+    }
+    fn keep_alive(&self) -> bool {
+        self.keep_alive
+    }
+    fn reset(&mut self) {
+        let rule = self.rule;
+        *self = HashLife::new();
+        self.rule = rule;
+    }
+    fn population(&self) -> usize {
+        self.node(self.root).population as usize
+    }
+    /// Get the positions of the alive cells.
+    /// The cells outside the i32 range, e.g. of spaceships after very many generations, are omitted.
+    fn live_cells(&self) -> Vec<(i32, i32)> {
+        let mut cells = Vec::with_capacity(self.population());
+        self.collect(self.root, self.origin(), self.origin(), &mut cells);
+        cells
+    }
+    /// Get the bounding box of the alive cells, clamped to the i32 range.
+    fn bounding_box(&self) -> Option<BoundingBox> {
+        let origin = self.origin();
+        let edge = |horizontal, far| {
+            let mut memo = HashMap::new();
+            self.edge(self.root, horizontal, far, &mut memo)
+                .map(|offset| (offset + origin).clamp(i32::MIN as i64, i32::MAX as i64) as i32)
+        };
+        Some(BoundingBox::new(edge(true, false)?, edge(false, false)?, edge(true, true)?, edge(false, true)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format;
+    use crate::model::{LifeGame, SparseLife, Topology};
+
+    const GLIDER: [(i32, i32); 5] = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+    const R_PENTOMINO: [(i32, i32); 5] = [(1, 0), (2, 0), (0, 1), (1, 1), (1, 2)];
+
+    fn gosper_glider_gun() -> Vec<(i32, i32)> {
+        let text = include_str!("../../patterns/gosper-glider-gun.rle");
+        format::parse(text).unwrap().get_cells().to_vec()
+    }
+
+    fn sorted<U: Universe>(universe: &U) -> Vec<(i32, i32)> {
+        let mut cells = universe.live_cells();
+        cells.sort_unstable();
+        cells
+    }
+
+    /// Check that stepping `2^k` generations at once matches the generations one by one.
+    fn assert_same_as_sparse(cells: &[(i32, i32)], max_k: u8) {
+        for k in 0..=max_k {
+            let mut hash_life = HashLife::from_cells(cells.iter().copied());
+            hash_life.step_pow2(k);
+            let mut sparse = SparseLife::from_cells(cells.iter().copied());
+            for _ in 0..1u64 << k {
+                sparse.next_generation();
+            }
+            assert_eq!(hash_life.get_generation(), sparse.get_generation(), "k = {}", k);
+            assert_eq!(sorted(&hash_life), sorted(&sparse), "k = {}", k);
+            assert_eq!(hash_life.population(), sparse.population(), "k = {}", k);
+        }
+    }
+
+    #[test]
+    fn step_pow2_matches_sparse_life() {
+        assert_same_as_sparse(&GLIDER, 8);
+        assert_same_as_sparse(&R_PENTOMINO, 8);
+        assert_same_as_sparse(&gosper_glider_gun(), 8);
+    }
+
+    #[test]
+    fn step_matches_life_game_on_plane() {
+        let (width, height, offset) = (160, 160, 60);
+        for cells in [GLIDER.to_vec(), R_PENTOMINO.to_vec(), gosper_glider_gun()] {
+            let mut life_game = LifeGame::new(width, height);
+            life_game.set_topology(Topology::Plane);
+            for &(x, y) in &cells {
+                life_game.set_alive(x + offset, y + offset, true);
+            }
+            let mut hash_life = HashLife::from_cells(cells.iter().map(|&(x, y)| (x + offset, y + offset)));
+            for _ in 0..50 {
+                life_game.next_generation();
+            }
+            hash_life.step(50);
+            assert_eq!(hash_life.get_generation(), 50);
+            assert_eq!(sorted(&hash_life), sorted(&life_game));
+        }
+    }
+
+    #[test]
+    fn step_counts_generations_beyond_max_step() {
+        let block = [(0, 0), (0, 1), (1, 0), (1, 1)];
+        let mut hash_life = HashLife::from_cells(block);
+        hash_life.step((1 << 63) | (1 << 60) | 3);
+        assert_eq!(hash_life.get_generation(), (1 << 63) | (1 << 60) | 3);
+        assert_eq!(sorted(&hash_life), block.to_vec());
+    }
+
+    #[test]
+    fn huge_step_keeps_the_universe_bounded() {
+        let mut hash_life = HashLife::from_cells(GLIDER);
+        hash_life.step(u64::MAX);
+        assert_eq!(hash_life.get_generation(), u64::MAX);
+        // The glider flew beyond the largest universe, so it was dropped.
+        assert_eq!(hash_life.population(), 0);
+        assert_eq!(hash_life.bounding_box(), None);
+        assert!(hash_life.live_cells().is_empty());
+        assert!(!hash_life.is_alive(0, 0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn step_pow2_rejects_too_many_generations() {
+        HashLife::new().step_pow2(64);
+    }
+
+    #[test]
+    fn live_cells_omits_cells_outside_i32() {
+        let mut hash_life = HashLife::from_cells(GLIDER);
+        // The glider moves by 2^33 cells, beyond the range of i32.
+        hash_life.step_pow2(35);
+        assert_eq!(hash_life.population(), 5);
+        assert!(hash_life.live_cells().is_empty());
+        let bounds = hash_life.bounding_box().unwrap();
+        assert_eq!(bounds.get_max(), (i32::MAX, i32::MAX));
+    }
+}
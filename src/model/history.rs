@@ -0,0 +1,273 @@
+/// history.rs
+/// - Undo and redo of the edits, and stepping back the generations.
+use std::collections::VecDeque;
+
+use super::{BitGrid, LifeGame, Universe};
+
+/// The number of commands kept to undo by default.
+const DEFAULT_UNDO_LIMIT: usize = 1000;
+/// The number of generations kept to step back by default.
+const DEFAULT_STEP_LIMIT: usize = 100;
+
+/// State of the whole board at a generation.
+/// The words of the cells are compressed by skipping the runs of empty words.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    width: usize,
+    height: usize,
+    /// The number of the empty words before each non-empty word, and the word.
+    words: Vec<(u32, u64)>,
+    generation: u64,
+}
+
+impl Snapshot {
+    /// Create a new snapshot of the cells at the generation.
+    pub fn new(cells: &BitGrid, generation: u64) -> Snapshot {
+        let mut words = Vec::new();
+        let mut empty = 0;
+        for &word in cells.get_words() {
+            if word == 0 {
+                empty += 1;
+            } else {
+                words.push((empty, word));
+                empty = 0;
+            }
+        }
+        Snapshot { width: cells.get_width(), height: cells.get_height(), words, generation }
+    }
+    /// Get the cells of the snapshot.
+    pub fn get_cells(&self) -> BitGrid {
+        let mut words = vec![0; self.width.div_ceil(64) * self.height];
+        let mut index = 0;
+        for &(empty, word) in &self.words {
+            index += empty as usize;
+            words[index] = word;
+            index += 1;
+        }
+        BitGrid::from_words(self.width, self.height, words).expect("snapshot words match the size")
+    }
+    /// Get the width and the height of the board.
+    pub fn get_size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+    /// Get the memory used by the compressed cells in bytes.
+    pub fn get_compressed_size(&self) -> usize {
+        self.words.len() * std::mem::size_of::<(u32, u64)>()
+    }
+    /// Get the generation of the snapshot.
+    pub fn get_generation(&self) -> u64 {
+        self.generation
+    }
+}
+
+/// Edit of the board which can be undone and redone.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Toggle the cells, e.g. by a click or by placing a pattern.
+    Toggle(Vec<(i32, i32)>),
+    /// Replace the whole board, e.g. by a clear, a resize or loading a pattern.
+    Replace { before: Snapshot, after: Snapshot },
+}
+
+impl Command {
+    /// Revert the command on the board.
+    pub fn undo(&self, life_game: &mut LifeGame) {
+        match self {
+            Command::Toggle(cells) => toggle(life_game, cells),
+            Command::Replace { before, .. } => life_game.restore(before),
+        }
+    }
+    /// Apply the command to the board again.
+    pub fn redo(&self, life_game: &mut LifeGame) {
+        match self {
+            Command::Toggle(cells) => toggle(life_game, cells),
+            Command::Replace { after, .. } => life_game.restore(after),
+        }
+    }
+}
+
+fn toggle(life_game: &mut LifeGame, cells: &[(i32, i32)]) {
+    for &(x, y) in cells {
+        let alive = life_game.is_alive(x, y);
+        life_game.set_alive(x, y, !alive);
+    }
+}
+
+/// Undo and redo stacks of the commands, and the snapshots of the previous generations.
+#[derive(Debug, Clone)]
+pub struct History {
+    undo: Vec<Command>,
+    redo: Vec<Command>,
+    steps: VecDeque<Snapshot>,
+    undo_limit: usize,
+    step_limit: usize,
+}
+
+impl Default for History {
+    fn default() -> History {
+        History::new(DEFAULT_UNDO_LIMIT, DEFAULT_STEP_LIMIT)
+    }
+}
+
+impl History {
+    /// Create a new history keeping up to `undo_limit` commands and `step_limit` generations.
+    pub fn new(undo_limit: usize, step_limit: usize) -> History {
+        History { undo: Vec::new(), redo: Vec::new(), steps: VecDeque::new(), undo_limit, step_limit }
+    }
+    /// Record the command which was applied to the board. The redo stack is cleared.
+    pub fn record(&mut self, command: Command) {
+        if matches!(&command, Command::Toggle(cells) if cells.is_empty()) {
+            return;
+        }
+        self.undo.push(command);
+        if self.undo.len() > self.undo_limit {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+    /// Record the board before it is replaced, and the board after that.
+    pub fn record_replace(&mut self, before: Snapshot, life_game: &LifeGame) {
+        self.record(Command::Replace { before, after: life_game.snapshot() });
+    }
+    /// Record the board before it advances a generation.
+    /// The commands are forgotten, since they were applied to a board which has since evolved.
+    pub fn record_step(&mut self, before: Snapshot) {
+        self.undo.clear();
+        self.redo.clear();
+        self.steps.push_back(before);
+        if self.steps.len() > self.step_limit {
+            self.steps.pop_front();
+        }
+    }
+    /// Undo the last command. Returns `false` if there is nothing to undo.
+    pub fn undo(&mut self, life_game: &mut LifeGame) -> bool {
+        let Some(command) = self.undo.pop() else {
+            return false;
+        };
+        command.undo(life_game);
+        self.redo.push(command);
+        true
+    }
+    /// Redo the last undone command. Returns `false` if there is nothing to redo.
+    pub fn redo(&mut self, life_game: &mut LifeGame) -> bool {
+        let Some(command) = self.redo.pop() else {
+            return false;
+        };
+        command.redo(life_game);
+        self.undo.push(command);
+        true
+    }
+    /// Restore the board of the previous generation. The step back itself can be undone.
+    /// Returns `false` if no previous generation is kept.
+    pub fn step_back(&mut self, life_game: &mut LifeGame) -> bool {
+        let Some(previous) = self.steps.pop_back() else {
+            return false;
+        };
+        let before = life_game.snapshot();
+        life_game.restore(&previous);
+        self.record_replace(before, life_game);
+        true
+    }
+    /// Check if there is a command to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+    /// Check if there is a command to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+    /// Check if there is a previous generation to step back to.
+    pub fn can_step_back(&self) -> bool {
+        !self.steps.is_empty()
+    }
+    /// Forget all the commands and the generations.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.steps.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Storage;
+
+    fn blinker() -> LifeGame {
+        let mut life_game = LifeGame::new(8, 8);
+        for x in 2..5 {
+            life_game.set_alive(x, 3, true);
+        }
+        life_game
+    }
+
+    #[test]
+    fn undo_and_redo_toggles() {
+        let mut life_game = blinker();
+        let mut history = History::default();
+        life_game.set_alive(0, 0, true);
+        history.record(Command::Toggle(vec![(0, 0)]));
+        assert!(history.undo(&mut life_game));
+        assert!(!life_game.is_alive(0, 0));
+        assert!(history.redo(&mut life_game));
+        assert!(life_game.is_alive(0, 0));
+        assert!(!history.redo(&mut life_game));
+    }
+
+    #[test]
+    fn step_forgets_the_toggles() {
+        let mut life_game = blinker();
+        let mut history = History::default();
+        life_game.set_alive(2, 3, false);
+        history.record(Command::Toggle(vec![(2, 3)]));
+        let before = life_game.snapshot();
+        history.record_step(before.clone());
+        life_game.next_generation();
+        let after = life_game.snapshot();
+
+        // Undoing the toggle would revive (2, 3) on the evolved board.
+        assert!(!history.can_undo());
+        assert!(!history.undo(&mut life_game));
+        assert_eq!(life_game.snapshot(), after);
+
+        // The step itself is still reverted by stepping back, which can be undone.
+        assert!(history.step_back(&mut life_game));
+        assert_eq!(life_game.snapshot(), before);
+        assert!(history.undo(&mut life_game));
+        assert_eq!(life_game.snapshot(), after);
+    }
+    #[test]
+    fn snapshot_round_trips_the_cells() {
+        // Two words per row, with the cells at the ends of the words.
+        let mut cells = BitGrid::new(70, 5);
+        for (x, y) in [(0, 0), (69, 0), (3, 2), (64, 4), (69, 4)] {
+            cells.set(x, y, true);
+        }
+        let snapshot = Snapshot::new(&cells, 7);
+        assert_eq!(snapshot.get_cells(), cells);
+        assert_eq!(snapshot.get_size(), (70, 5));
+        assert_eq!(snapshot.get_generation(), 7);
+        // Only the 4 non-empty words of the 10 are kept.
+        assert_eq!(snapshot.get_compressed_size(), 4 * std::mem::size_of::<(u32, u64)>());
+    }
+
+    #[test]
+    fn snapshot_of_an_empty_board_is_empty() {
+        let cells = BitGrid::new(130, 3);
+        let snapshot = Snapshot::new(&cells, 0);
+        assert_eq!(snapshot.get_compressed_size(), 0);
+        assert_eq!(snapshot.get_cells(), cells);
+    }
+
+    #[test]
+    fn snapshot_restores_both_storages() {
+        let snapshot = blinker().snapshot();
+        for storage in [Storage::Cells, Storage::BitPacked] {
+            let mut life_game = LifeGame::with_storage(3, 3, storage);
+            life_game.restore(&snapshot);
+            assert_eq!((life_game.get_width(), life_game.get_height()), (8, 8));
+            assert_eq!(life_game.live_cells(), vec![(2, 3), (3, 3), (4, 3)]);
+            assert_eq!(life_game.snapshot(), snapshot);
+        }
+    }
+}
@@ -0,0 +1,594 @@
+/// model/mod.rs
+///  - Model of the life-game.
+mod bitgrid;
+mod census;
+mod condition;
+mod cycle;
+mod engine;
+mod hashlife;
+mod history;
+mod placement;
+mod rule;
+mod run;
+mod soup;
+mod sparse;
+mod stats;
+mod timeline;
+mod topology;
+
+use std::collections::HashSet;
+use std::thread;
+
+pub use bitgrid::BitGrid;
+pub use census::{Census, CensusEntry};
+pub use condition::{RunCondition, RunUntil};
+pub use cycle::{Cycle, CycleDetector};
+pub use engine::Engine;
+pub use hashlife::{HashLife, TreeNode};
+pub use history::{Command, History, Snapshot};
+pub use placement::{PasteMode, Transform};
+pub use rule::{Rule, RuleError};
+pub use run::{EndReason, ExtinctionPolicy, RunEnd};
+pub use soup::{Random, Soup, Symmetry};
+pub use sparse::SparseLife;
+pub use stats::{GenerationStats, Statistics, CSV_HEADER};
+pub use timeline::Timeline;
+pub use topology::Topology;
+
+/// The largest width and height of the board.
+pub const MAX_BOARD_SIZE: usize = 4096;
+
+/// Cell
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cell {
+    x: i32,
+    y: i32,
+    alive: bool,
+}
+
+impl Cell {
+    /// Create a new cell.
+    pub fn new(x: i32, y: i32, alive: bool) -> Cell {
+        Cell { x, y, alive }
+    }
+    /// Check if the cell is alive.
+    pub fn is_alive(&self) -> bool {
+        self.alive
+    }
+    /// Set the cell alive or dead.
+    pub fn set_alive(&mut self, alive: bool) {
+        self.alive = alive;
+    }
+    /// Get the position of the cell.
+    pub fn get_position(&self) -> (i32, i32) {
+        (self.x, self.y)
+    }
+}
+
+/// Bounding box of the alive cells. Both edges are inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundingBox {
+    left: i32,
+    top: i32,
+    right: i32,
+    bottom: i32,
+}
+
+impl BoundingBox {
+    /// Create a new bounding box.
+    pub fn new(left: i32, top: i32, right: i32, bottom: i32) -> BoundingBox {
+        BoundingBox { left, top, right, bottom }
+    }
+    /// Get the bounding box of the cells. Returns `None` if there is no cell.
+    pub fn from_cells<I: IntoIterator<Item = (i32, i32)>>(cells: I) -> Option<BoundingBox> {
+        cells.into_iter().fold(None, |bounds, (x, y)| match bounds {
+            None => Some(BoundingBox::new(x, y, x, y)),
+            Some(bounds) => Some(BoundingBox::new(
+                bounds.left.min(x),
+                bounds.top.min(y),
+                bounds.right.max(x),
+                bounds.bottom.max(y),
+            )),
+        })
+    }
+    /// Get the top-left position.
+    pub fn get_min(&self) -> (i32, i32) {
+        (self.left, self.top)
+    }
+    /// Get the bottom-right position.
+    pub fn get_max(&self) -> (i32, i32) {
+        (self.right, self.bottom)
+    }
+    /// Get the width of the bounding box.
+    pub fn get_width(&self) -> u64 {
+        (self.right as i64 - self.left as i64 + 1) as u64
+    }
+    /// Get the height of the bounding box.
+    pub fn get_height(&self) -> u64 {
+        (self.bottom as i64 - self.top as i64 + 1) as u64
+    }
+    /// Check if the position is inside of the bounding box.
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        (self.left..=self.right).contains(&x) && (self.top..=self.bottom).contains(&y)
+    }
+}
+
+/// Common API of the life-game universes.
+pub trait Universe {
+    /// Get the generation of the universe.
+    fn get_generation(&self) -> u64;
+    /// Get the rule of the universe.
+    fn get_rule(&self) -> &Rule;
+    /// Check if the cell at the position is alive.
+    fn is_alive(&self, x: i32, y: i32) -> bool;
+    /// Set the cell at the position alive or dead.
+    fn set_alive(&mut self, x: i32, y: i32, alive: bool);
+    /// Update the state of the universe to the next generation.
+    fn next_generation(&mut self);
+    /// Check if any cell was alive after the last generation.
+    fn keep_alive(&self) -> bool;
+    /// Kill all the cells and reset the generation.
+    fn reset(&mut self);
+    /// Get the number of alive cells.
+    fn population(&self) -> usize;
+    /// Get the positions of the alive cells.
+    fn live_cells(&self) -> Vec<(i32, i32)>;
+    /// Get the bounding box of the alive cells.
+    fn bounding_box(&self) -> Option<BoundingBox>;
+}
+
+/// Point of the board which stays in place when the board is resized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Anchor {
+    /// The cells keep their positions from the top-left corner.
+    #[default]
+    TopLeft,
+    /// The cells keep their positions from the center.
+    Center,
+}
+
+impl Anchor {
+    /// All the anchors.
+    pub const ALL: [Anchor; 2] = [Anchor::TopLeft, Anchor::Center];
+
+    /// Get the name of the anchor.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Anchor::TopLeft => "Top-left",
+            Anchor::Center => "Center",
+        }
+    }
+}
+
+/// How the cells of the board are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Storage {
+    /// A `Cell` per cell.
+    #[default]
+    Cells,
+    /// A bit per cell, stepped with word-parallel bit operations.
+    BitPacked,
+}
+
+/// Cells of the board.
+#[derive(Debug, Clone)]
+enum Grid {
+    Cells(Vec<Cell>),
+    Bits(BitGrid),
+}
+
+/// LifeGame model
+#[derive(Debug)]
+pub struct LifeGame {
+    width: usize,
+    height: usize,
+    cells: Grid,
+    generation: u64,
+    keep_alive: bool,
+    rule: Rule,
+    topology: Topology,
+    threads: usize,
+}
+
+impl LifeGame {
+    /// Create a new life-game model.
+    pub fn new(width: usize, height: usize) -> LifeGame {
+        LifeGame::with_storage(width, height, Storage::default())
+    }
+    /// Create a new life-game model with the storage of the cells.
+    pub fn with_storage(width: usize, height: usize, storage: Storage) -> LifeGame {
+        LifeGame {
+            width,
+            height,
+            cells: LifeGame::new_grid(width, height, storage),
+            generation: 0,
+            keep_alive: false,
+            rule: Rule::default(),
+            topology: Topology::default(),
+            threads: 1,
+        }
+    }
+    fn new_grid(width: usize, height: usize, storage: Storage) -> Grid {
+        match storage {
+            Storage::Cells => {
+                let mut cells = Vec::new();
+                for i in 0 .. width * height {
+                    let x = (i % width) as i32;
+                    let y = (i / width) as i32;
+                    cells.push(Cell::new(x, y, false));
+                }
+                Grid::Cells(cells)
+            }
+            Storage::BitPacked => Grid::Bits(BitGrid::new(width, height)),
+        }
+    }
+    /// Get the storage of the cells.
+    pub fn get_storage(&self) -> Storage {
+        match self.cells {
+            Grid::Cells(_) => Storage::Cells,
+            Grid::Bits(_) => Storage::BitPacked,
+        }
+    }
+    /// Change the storage of the cells, keeping their states.
+    pub fn set_storage(&mut self, storage: Storage) {
+        if storage == self.get_storage() {
+            return;
+        }
+        let mut cells = LifeGame::new_grid(self.width, self.height, storage);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if self.is_alive_at(x, y) {
+                    LifeGame::set_alive_at(&mut cells, self.width, x, y, true);
+                }
+            }
+        }
+        self.cells = cells;
+    }
+    /// Resize the board. The alive cells keep their positions from the anchor,
+    /// the cells outside of the new board are cropped and the new cells are dead.
+    pub fn resize(&mut self, width: usize, height: usize, anchor: Anchor) {
+        let (dx, dy) = match anchor {
+            Anchor::TopLeft => (0, 0),
+            Anchor::Center => (
+                (width as i64 - self.width as i64) / 2,
+                (height as i64 - self.height as i64) / 2,
+            ),
+        };
+        let mut cells = LifeGame::new_grid(width, height, self.get_storage());
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let (new_x, new_y) = (x as i64 + dx, y as i64 + dy);
+                if self.is_alive_at(x, y)
+                    && (0..width as i64).contains(&new_x)
+                    && (0..height as i64).contains(&new_y)
+                {
+                    LifeGame::set_alive_at(&mut cells, width, new_x as usize, new_y as usize, true);
+                }
+            }
+        }
+        self.width = width;
+        self.height = height;
+        self.cells = cells;
+    }
+    /// Get a copy of the cells as a bit-packed board.
+    pub fn to_bit_grid(&self) -> BitGrid {
+        match &self.cells {
+            Grid::Cells(_) => {
+                let mut bits = BitGrid::new(self.width, self.height);
+                for y in 0..self.height {
+                    for x in 0..self.width {
+                        bits.set(x, y, self.is_alive_at(x, y));
+                    }
+                }
+                bits
+            }
+            Grid::Bits(bits) => bits.clone(),
+        }
+    }
+    /// Get the snapshot of the cells and the generation.
+    pub fn snapshot(&self) -> Snapshot {
+        match &self.cells {
+            Grid::Bits(bits) => Snapshot::new(bits, self.generation),
+            Grid::Cells(_) => Snapshot::new(&self.to_bit_grid(), self.generation),
+        }
+    }
+    /// Restore the cells, the size and the generation of the snapshot.
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        let bits = snapshot.get_cells();
+        self.keep_alive = bits.any_alive();
+        self.cells = match self.get_storage() {
+            Storage::Cells => {
+                let mut cells = LifeGame::new_grid(bits.get_width(), bits.get_height(), Storage::Cells);
+                for y in 0..bits.get_height() {
+                    for x in 0..bits.get_width() {
+                        if bits.get(x, y) {
+                            LifeGame::set_alive_at(&mut cells, bits.get_width(), x, y, true);
+                        }
+                    }
+                }
+                cells
+            }
+            Storage::BitPacked => Grid::Bits(bits),
+        };
+        (self.width, self.height) = snapshot.get_size();
+        self.generation = snapshot.get_generation();
+    }
+    /// Get the width of the life-game.
+    pub fn get_width(&self) -> usize {
+        self.width
+    }
+    /// Get the height of the life-game.
+    pub fn get_height(&self) -> usize {
+        self.height
+    }
+    /// Get the generation of the life-game.
+    pub fn get_generation(&self) -> u64 {
+        self.generation
+    }
+    /// Get the rule of the life-game.
+    pub fn get_rule(&self) -> &Rule {
+        &self.rule
+    }
+    /// Set the rule used by the following generations.
+    pub fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+    }
+    /// Get the topology of the life-game.
+    pub fn get_topology(&self) -> Topology {
+        self.topology
+    }
+    /// Set the topology of the board edges.
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+    }
+
+    /// Get the number of threads used to compute a generation.
+    pub fn get_threads(&self) -> usize {
+        self.threads
+    }
+    /// Set the number of threads used to compute a generation.
+    /// The board is split into bands of rows, one per thread. `0` uses all the cores.
+    /// The result is the same as the serial computation for any number of threads.
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = match threads {
+            0 => thread::available_parallelism().map_or(1, |threads| threads.get()),
+            threads => threads,
+        };
+    }
+    /// Get the number of rows of a band computed by a thread.
+    fn band_height(&self) -> usize {
+        self.height.div_ceil(self.threads).max(1)
+    }
+
+    /// Calculatet the vector index from the position.
+    /// Returns `None` if the position is outside of the board and the topology does not wrap it,
+    /// since on the plane and the cylinder such a position has no cell.
+    pub fn get_index(&self, x: i32, y: i32) -> Option<usize> {
+        let (x, y) = self.topology.map(x, y, self.width, self.height)?;
+        Some(y as usize * self.width + x as usize)
+    }
+    /// Get the cell at the position.
+    /// The cell is returned by value, since the bit-packed storage has no `Cell` to borrow.
+    /// The cells are changed by `set_alive`.
+    pub fn get_cell(&self, x: i32, y: i32) -> Option<Cell> {
+        let (x, y) = self.topology.map(x, y, self.width, self.height)?;
+        Some(Cell::new(x, y, self.is_alive_at(x as usize, y as usize)))
+    }
+    /// Set the cell at the position alive or dead.
+    /// Returns `false` if the position is outside of the board and the topology does not wrap it.
+    pub fn set_alive(&mut self, x: i32, y: i32, alive: bool) -> bool {
+        match self.topology.map(x, y, self.width, self.height) {
+            Some((x, y)) => {
+                LifeGame::set_alive_at(&mut self.cells, self.width, x as usize, y as usize, alive);
+                true
+            }
+            None => false,
+        }
+    }
+    fn is_alive_at(&self, x: usize, y: usize) -> bool {
+        match &self.cells {
+            Grid::Cells(cells) => cells[y * self.width + x].is_alive(),
+            Grid::Bits(bits) => bits.get(x, y),
+        }
+    }
+    fn set_alive_at(cells: &mut Grid, width: usize, x: usize, y: usize, alive: bool) {
+        match cells {
+            Grid::Cells(cells) => cells[y * width + x].set_alive(alive),
+            Grid::Bits(bits) => bits.set(x, y, alive),
+        }
+    }
+    /// Update the state of the game to the next generation.
+    pub fn next_generation(&mut self) {
+        match &self.cells {
+            Grid::Cells(cells) => {
+                let cells = self.next_cells(cells);
+                self.keep_alive = cells.iter().any(|cell| cell.is_alive());
+                self.cells = Grid::Cells(cells);
+            }
+            Grid::Bits(bits) => {
+                let bits = if self.threads > 1 {
+                    bits.next_generation_parallel(&self.rule, self.topology, self.band_height())
+                } else {
+                    bits.next_generation(&self.rule, self.topology)
+                };
+                self.keep_alive = bits.any_alive();
+                self.cells = Grid::Bits(bits);
+            }
+        }
+        self.generation += 1;
+    }
+    /// Compute the next generation cell by cell.
+    fn next_cells(&self, cells: &[Cell]) -> Vec<Cell> {
+        let mut new_cells = cells.to_vec();
+        if self.threads > 1 {
+            thread::scope(|scope| {
+                for band in new_cells.chunks_mut((self.band_height() * self.width).max(1)) {
+                    scope.spawn(|| self.next_band(band));
+                }
+            });
+        } else {
+            self.next_band(&mut new_cells);
+        }
+        new_cells
+    }
+    /// Compute the next generation of the band, which holds the current states.
+    fn next_band(&self, band: &mut [Cell]) {
+        for cell in band {
+            let (x, y) = cell.get_position();
+            let alive_neighbors = self.count_alive_neighbors(x, y);
+            let new_state = self.rule.next_state(cell.is_alive(), alive_neighbors);
+            cell.set_alive(new_state);
+        }
+    }
+
+    pub fn keep_alive(&self) -> bool {
+        self.keep_alive
+    }
+
+    pub fn reset_generation(&mut self) {
+        self.generation = 0;
+    }
+
+    /// Set the generation counter, e.g. after the board is computed by another engine.
+    pub fn set_generation(&mut self, generation: u64) {
+        self.generation = generation;
+    }
+
+    /// Count the number of alive neighbors of a cell at a given position.
+    /// Neighbors outside of the board are found through the topology.
+    fn count_alive_neighbors(&self, x: i32, y: i32) -> usize {
+        let mut count = 0;
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                if (dx != 0 || dy != 0)
+                    && let Some(neighbor) = self.get_cell(x + dx, y + dy)
+                    && neighbor.is_alive()
+                {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    /// Reset the game.
+    pub fn reset(&mut self) {
+        match &mut self.cells {
+            Grid::Cells(cells) => {
+                for cell in cells {
+                    cell.set_alive(false);
+                }
+            }
+            Grid::Bits(bits) => bits.clear(),
+        }
+        self.generation = 0;
+    }
+
+    /// Place the pattern with its top-left corner at `(x, y)`, after transforming it.
+    /// The cells under the bounding box of the transformed pattern are combined by the paste mode,
+    /// and the cells outside of the board are cropped.
+    /// Returns the positions of the cells which were toggled.
+    pub fn place_pattern(
+        &mut self,
+        cells: &[(i32, i32)],
+        x: i32,
+        y: i32,
+        transform: Transform,
+        mode: PasteMode,
+    ) -> Vec<(i32, i32)> {
+        let cells = transform.apply_to_cells(cells);
+        let Some(bounds) = BoundingBox::from_cells(cells.iter().copied()) else {
+            return Vec::new();
+        };
+        let pattern: HashSet<(i32, i32)> = cells.into_iter().collect();
+        let mut toggled = Vec::new();
+        for dy in 0..bounds.get_height() as i32 {
+            for dx in 0..bounds.get_width() as i32 {
+                let (column, row) = (x + dx, y + dy);
+                if !(0..self.width as i32).contains(&column) || !(0..self.height as i32).contains(&row) {
+                    continue;
+                }
+                let alive = self.is_alive_at(column as usize, row as usize);
+                let next = mode.apply(alive, pattern.contains(&(dx, dy)));
+                if next != alive {
+                    LifeGame::set_alive_at(&mut self.cells, self.width, column as usize, row as usize, next);
+                    toggled.push((column, row));
+                }
+            }
+        }
+        toggled
+    }
+    /// Fill the area, or the whole board if it is `None`, with the random soup.
+    /// The area is cropped to the board, and shrunk to the centered square if the symmetry needs it.
+    /// Returns the positions of the cells which were toggled.
+    pub fn fill_soup(&mut self, soup: &Soup, area: Option<BoundingBox>) -> Vec<(i32, i32)> {
+        let area = area.unwrap_or(BoundingBox::new(0, 0, self.width as i32 - 1, self.height as i32 - 1));
+        let (left, top) = area.get_min();
+        let (right, bottom) = area.get_max();
+        let (mut left, mut top) = (left.max(0), top.max(0));
+        let (right, bottom) = (right.min(self.width as i32 - 1), bottom.min(self.height as i32 - 1));
+        if left > right || top > bottom {
+            return Vec::new();
+        }
+        let (mut width, mut height) = ((right - left + 1) as usize, (bottom - top + 1) as usize);
+        if soup.get_symmetry().is_square() {
+            let side = width.min(height);
+            left += ((width - side) / 2) as i32;
+            top += ((height - side) / 2) as i32;
+            (width, height) = (side, side);
+        }
+        let mut toggled = Vec::new();
+        for (i, alive) in soup.generate(width, height).into_iter().enumerate() {
+            let (x, y) = (left as usize + i % width, top as usize + i / width);
+            if self.is_alive_at(x, y) != alive {
+                LifeGame::set_alive_at(&mut self.cells, self.width, x, y, alive);
+                toggled.push((x as i32, y as i32));
+            }
+        }
+        toggled
+    }
+}
+
+impl Universe for LifeGame {
+    fn get_generation(&self) -> u64 {
+        self.generation
+    }
+    fn get_rule(&self) -> &Rule {
+        &self.rule
+    }
+    fn is_alive(&self, x: i32, y: i32) -> bool {
+        self.get_cell(x, y).is_some_and(|cell| cell.is_alive())
+    }
+    fn set_alive(&mut self, x: i32, y: i32, alive: bool) {
+        LifeGame::set_alive(self, x, y, alive);
+    }
+    fn next_generation(&mut self) {
+        LifeGame::next_generation(self);
+    }
+    fn keep_alive(&self) -> bool {
+        self.keep_alive
+    }
+    fn reset(&mut self) {
+        LifeGame::reset(self);
+    }
+    fn population(&self) -> usize {
+        match &self.cells {
+            Grid::Cells(cells) => cells.iter().filter(|cell| cell.is_alive()).count(),
+            Grid::Bits(bits) => bits.count_alive(),
+        }
+    }
+    fn live_cells(&self) -> Vec<(i32, i32)> {
+        let mut cells = Vec::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if self.is_alive_at(x, y) {
+                    cells.push((x as i32, y as i32));
+                }
+            }
+        }
+        cells
+    }
+    fn bounding_box(&self) -> Option<BoundingBox> {
+        BoundingBox::from_cells(self.live_cells())
+    }
+}
@@ -0,0 +1,209 @@
+/// placement.rs
+/// - Transforms and paste modes to place a pattern on the board.
+use super::BoundingBox;
+
+/// One of the 8 rotations and reflections of a pattern.
+/// The rotations are clockwise on the screen, where y grows downwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Transform {
+    #[default]
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    /// Mirror left and right.
+    FlipHorizontal,
+    /// Mirror top and bottom.
+    FlipVertical,
+    /// Mirror along the diagonal from the top-left to the bottom-right.
+    FlipDiagonal,
+    /// Mirror along the diagonal from the top-right to the bottom-left.
+    FlipAntiDiagonal,
+}
+
+impl Transform {
+    pub const ALL: [Transform; 8] = [
+        Transform::Identity,
+        Transform::Rotate90,
+        Transform::Rotate180,
+        Transform::Rotate270,
+        Transform::FlipHorizontal,
+        Transform::FlipVertical,
+        Transform::FlipDiagonal,
+        Transform::FlipAntiDiagonal,
+    ];
+
+    /// Get the name of the transform.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Transform::Identity => "As is",
+            Transform::Rotate90 => "Rotate 90",
+            Transform::Rotate180 => "Rotate 180",
+            Transform::Rotate270 => "Rotate 270",
+            Transform::FlipHorizontal => "Flip horizontal",
+            Transform::FlipVertical => "Flip vertical",
+            Transform::FlipDiagonal => "Flip diagonal",
+            Transform::FlipAntiDiagonal => "Flip anti-diagonal",
+        }
+    }
+    /// Transform the position around the origin.
+    pub fn apply(&self, x: i32, y: i32) -> (i32, i32) {
+        match self {
+            Transform::Identity => (x, y),
+            Transform::Rotate90 => (-y, x),
+            Transform::Rotate180 => (-x, -y),
+            Transform::Rotate270 => (y, -x),
+            Transform::FlipHorizontal => (-x, y),
+            Transform::FlipVertical => (x, -y),
+            Transform::FlipDiagonal => (y, x),
+            Transform::FlipAntiDiagonal => (-y, -x),
+        }
+    }
+    /// Transform the cells, moving the top-left corner of their bounding box to the origin.
+    pub fn apply_to_cells(&self, cells: &[(i32, i32)]) -> Vec<(i32, i32)> {
+        let cells: Vec<(i32, i32)> = cells.iter().map(|&(x, y)| self.apply(x, y)).collect();
+        let Some(bounds) = BoundingBox::from_cells(cells.iter().copied()) else {
+            return cells;
+        };
+        let (left, top) = bounds.get_min();
+        cells.into_iter().map(|(x, y)| (x - left, y - top)).collect()
+    }
+}
+
+/// How the cells of a placed pattern are combined with the cells on the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PasteMode {
+    /// Add the alive cells of the pattern.
+    #[default]
+    Or,
+    /// Toggle the cells under the alive cells of the pattern.
+    Xor,
+    /// Keep the cells alive only under the alive cells of the pattern.
+    And,
+    /// Replace the cells under the bounding box of the pattern.
+    Copy,
+}
+
+impl PasteMode {
+    pub const ALL: [PasteMode; 4] = [PasteMode::Or, PasteMode::Xor, PasteMode::And, PasteMode::Copy];
+
+    /// Get the name of the paste mode.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PasteMode::Or => "OR",
+            PasteMode::Xor => "XOR",
+            PasteMode::And => "AND",
+            PasteMode::Copy => "COPY",
+        }
+    }
+    /// Get the new state of a cell under the bounding box of the pattern.
+    pub fn apply(&self, alive: bool, pattern_alive: bool) -> bool {
+        match self {
+            PasteMode::Or => alive || pattern_alive,
+            PasteMode::Xor => alive != pattern_alive,
+            PasteMode::And => alive && pattern_alive,
+            PasteMode::Copy => pattern_alive,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{LifeGame, Universe};
+
+    /// Points which tell all the transforms apart.
+    const POINTS: [(i32, i32); 2] = [(1, 2), (3, -5)];
+
+    /// Find the transform which is the same as applying `first` and then `second`.
+    fn compose(first: Transform, second: Transform) -> Option<Transform> {
+        Transform::ALL.into_iter().find(|transform| {
+            POINTS.iter().all(|&(x, y)| {
+                let (x1, y1) = first.apply(x, y);
+                transform.apply(x, y) == second.apply(x1, y1)
+            })
+        })
+    }
+
+    fn sorted(mut cells: Vec<(i32, i32)>) -> Vec<(i32, i32)> {
+        cells.sort_unstable();
+        cells
+    }
+
+    #[test]
+    fn rotations_compose() {
+        use Transform::*;
+        assert_eq!(compose(Rotate90, Rotate90), Some(Rotate180));
+        assert_eq!(compose(Rotate90, Rotate180), Some(Rotate270));
+        assert_eq!(compose(Rotate270, Rotate90), Some(Identity));
+        assert_eq!(compose(Rotate180, Rotate180), Some(Identity));
+    }
+
+    #[test]
+    fn flips_compose() {
+        use Transform::*;
+        for flip in [FlipHorizontal, FlipVertical, FlipDiagonal, FlipAntiDiagonal] {
+            assert_eq!(compose(flip, flip), Some(Identity), "{:?}", flip);
+        }
+        assert_eq!(compose(FlipHorizontal, FlipVertical), Some(Rotate180));
+        assert_eq!(compose(FlipDiagonal, FlipAntiDiagonal), Some(Rotate180));
+        assert_eq!(compose(Rotate90, FlipHorizontal), Some(FlipDiagonal));
+        assert_eq!(compose(FlipHorizontal, Rotate90), Some(FlipAntiDiagonal));
+    }
+
+    #[test]
+    fn transforms_form_a_group() {
+        for first in Transform::ALL {
+            assert!(Transform::ALL.iter().any(|&second| compose(first, second) == Some(Transform::Identity)));
+            for second in Transform::ALL {
+                assert!(compose(first, second).is_some(), "{:?} {:?}", first, second);
+            }
+        }
+    }
+
+    #[test]
+    fn apply_to_cells_moves_the_pattern_to_the_origin() {
+        // An L of 2 x 3 cells.
+        let cells = [(0, 0), (0, 1), (0, 2), (1, 2)];
+        assert_eq!(sorted(Transform::Identity.apply_to_cells(&cells)), sorted(cells.to_vec()));
+        assert_eq!(sorted(Transform::Rotate90.apply_to_cells(&cells)), vec![(0, 0), (0, 1), (1, 0), (2, 0)]);
+        assert_eq!(sorted(Transform::FlipHorizontal.apply_to_cells(&cells)), vec![(0, 2), (1, 0), (1, 1), (1, 2)]);
+        assert!(Transform::Rotate90.apply_to_cells(&[]).is_empty());
+    }
+
+    #[test]
+    fn paste_modes_combine_the_cells() {
+        let table = [(false, false), (false, true), (true, false), (true, true)];
+        let states = |mode: PasteMode| table.map(|(alive, pattern_alive)| mode.apply(alive, pattern_alive));
+        assert_eq!(states(PasteMode::Or), [false, true, true, true]);
+        assert_eq!(states(PasteMode::Xor), [false, true, true, false]);
+        assert_eq!(states(PasteMode::And), [false, false, false, true]);
+        assert_eq!(states(PasteMode::Copy), [false, true, false, true]);
+    }
+
+    #[test]
+    fn place_pattern_combines_the_bounding_box_only() {
+        // Two cells with a hole between them, placed on a cell, over a cell in the hole and beside a cell.
+        let pattern = [(0, 0), (2, 0)];
+        let place = |mode: PasteMode| {
+            let mut life_game = LifeGame::new(8, 8);
+            for (x, y) in [(1, 2), (2, 2), (5, 2)] {
+                life_game.set_alive(x, y, true);
+            }
+            let toggled = life_game.place_pattern(&pattern, 1, 2, Transform::Identity, mode);
+            (sorted(life_game.live_cells()), sorted(toggled))
+        };
+        assert_eq!(place(PasteMode::Or), (vec![(1, 2), (2, 2), (3, 2), (5, 2)], vec![(3, 2)]));
+        assert_eq!(place(PasteMode::Xor), (vec![(2, 2), (3, 2), (5, 2)], vec![(1, 2), (3, 2)]));
+        assert_eq!(place(PasteMode::And), (vec![(1, 2), (5, 2)], vec![(2, 2)]));
+        assert_eq!(place(PasteMode::Copy), (vec![(1, 2), (3, 2), (5, 2)], vec![(2, 2), (3, 2)]));
+    }
+
+    #[test]
+    fn place_pattern_crops_the_cells_outside_of_the_board() {
+        let mut life_game = LifeGame::new(4, 4);
+        let toggled = life_game.place_pattern(&[(0, 0), (1, 0), (2, 0)], 2, 3, Transform::Identity, PasteMode::Or);
+        assert_eq!(sorted(toggled), vec![(2, 3), (3, 3)]);
+        assert_eq!(life_game.population(), 2);
+    }
+}
@@ -0,0 +1,215 @@
+/// rule.rs
+/// - Birth/survival rule of the life-game.
+use std::fmt;
+use std::str::FromStr;
+
+/// Error of parsing a rulestring.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleError {
+    /// The rulestring is empty.
+    Empty,
+    /// The rulestring has no '/' between the birth and survival parts.
+    MissingSeparator,
+    /// The rulestring has a character which is not allowed.
+    InvalidCharacter(char),
+    /// The neighbor count is out of the range 0 to 8.
+    InvalidCount(u32),
+    /// The same part (B or S) is given twice.
+    DuplicatePart(char),
+    /// The rule has B0, which the universe cannot simulate.
+    UnsupportedB0,
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleError::Empty => write!(f, "rule is empty"),
+            RuleError::MissingSeparator => write!(f, "rule has no '/' separator"),
+            RuleError::InvalidCharacter(c) => write!(f, "invalid character '{}' in rule", c),
+            RuleError::InvalidCount(n) => write!(f, "neighbor count {} is out of range 0-8", n),
+            RuleError::DuplicatePart(c) => write!(f, "part '{}' is given twice", c),
+            RuleError::UnsupportedB0 => write!(f, "rules with B0 are not supported by this universe"),
+        }
+    }
+}
+
+impl std::error::Error for RuleError {}
+
+/// Outer-totalistic rule. Each bit `n` of the masks is set
+/// if a cell is born (or survives) with `n` alive neighbors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rule {
+    birth: u16,
+    survival: u16,
+}
+
+impl Rule {
+    /// Conway's Game of Life (B3/S23).
+    pub const CONWAY: Rule = Rule { birth: 1 << 3, survival: (1 << 2) | (1 << 3) };
+
+    /// Create a new rule from the neighbor counts.
+    pub fn new(birth: &[u8], survival: &[u8]) -> Result<Rule, RuleError> {
+        Ok(Rule {
+            birth: Rule::to_mask(birth)?,
+            survival: Rule::to_mask(survival)?,
+        })
+    }
+    fn to_mask(counts: &[u8]) -> Result<u16, RuleError> {
+        let mut mask = 0;
+        for &count in counts {
+            if count > 8 {
+                return Err(RuleError::InvalidCount(count as u32));
+            }
+            mask |= 1 << count;
+        }
+        Ok(mask)
+    }
+    /// Parse the neighbor counts of one part of the rulestring.
+    fn parse_counts(digits: &str) -> Result<u16, RuleError> {
+        let mut mask = 0;
+        for c in digits.chars() {
+            match c.to_digit(10) {
+                Some(n) if n <= 8 => mask |= 1 << n,
+                Some(n) => return Err(RuleError::InvalidCount(n)),
+                None => return Err(RuleError::InvalidCharacter(c)),
+            }
+        }
+        Ok(mask)
+    }
+    /// Check if a dead cell with `neighbors` alive neighbors is born.
+    pub fn is_birth(&self, neighbors: usize) -> bool {
+        neighbors <= 8 && self.birth & (1 << neighbors) != 0
+    }
+    /// Check if an alive cell with `neighbors` alive neighbors survives.
+    pub fn is_survival(&self, neighbors: usize) -> bool {
+        neighbors <= 8 && self.survival & (1 << neighbors) != 0
+    }
+    /// Get the next state of a cell.
+    pub fn next_state(&self, alive: bool, neighbors: usize) -> bool {
+        if alive {
+            self.is_survival(neighbors)
+        } else {
+            self.is_birth(neighbors)
+        }
+    }
+    /// Get the neighbor counts of birth.
+    pub fn get_birth(&self) -> Vec<u8> {
+        (0..=8).filter(|n| self.birth & (1 << n) != 0).collect()
+    }
+    /// Get the neighbor counts of survival.
+    pub fn get_survival(&self) -> Vec<u8> {
+        (0..=8).filter(|n| self.survival & (1 << n) != 0).collect()
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Rule {
+        Rule::CONWAY
+    }
+}
+
+impl FromStr for Rule {
+    type Err = RuleError;
+
+    /// Parse `B36/S23` (parts in any order, case-insensitive)
+    /// or the legacy `23/36` (survival/birth) notation.
+    /// An empty part, as in `B3/`, has no neighbor counts.
+    fn from_str(s: &str) -> Result<Rule, RuleError> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(RuleError::Empty);
+        }
+        let (first, second) = s.split_once('/').ok_or(RuleError::MissingSeparator)?;
+        let (first, second) = (first.trim(), second.trim());
+        let is_prefixed = |part: &str| part.starts_with(|c: char| c.is_ascii_alphabetic());
+        if !is_prefixed(first) && !is_prefixed(second) {
+            return Ok(Rule {
+                birth: Rule::parse_counts(second)?,
+                survival: Rule::parse_counts(first)?,
+            });
+        }
+        let mut birth = None;
+        let mut survival = None;
+        for part in [first, second] {
+            let mut chars = part.chars();
+            let Some(prefix) = chars.next() else {
+                continue;
+            };
+            let counts = Rule::parse_counts(chars.as_str())?;
+            let target = match prefix.to_ascii_uppercase() {
+                'B' => &mut birth,
+                'S' => &mut survival,
+                _ => return Err(RuleError::InvalidCharacter(prefix)),
+            };
+            if target.replace(counts).is_some() {
+                return Err(RuleError::DuplicatePart(prefix.to_ascii_uppercase()));
+            }
+        }
+        Ok(Rule {
+            birth: birth.unwrap_or(0),
+            survival: survival.unwrap_or(0),
+        })
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = |counts: Vec<u8>| counts.iter().map(|n| n.to_string()).collect::<String>();
+        write!(f, "B{}/S{}", digits(self.get_birth()), digits(self.get_survival()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_prefixed_rules() {
+        let rule: Rule = "B36/S23".parse().unwrap();
+        assert_eq!(rule.get_birth(), vec![3, 6]);
+        assert_eq!(rule.get_survival(), vec![2, 3]);
+        assert_eq!(" s23 / b36 ".parse::<Rule>(), Ok(rule));
+        assert_eq!("B3/S23".parse::<Rule>(), Ok(Rule::CONWAY));
+    }
+
+    #[test]
+    fn parses_legacy_rules() {
+        assert_eq!("23/3".parse::<Rule>(), Ok(Rule::CONWAY));
+        let rule: Rule = "/2".parse().unwrap();
+        assert_eq!(rule.get_birth(), vec![2]);
+        assert!(rule.get_survival().is_empty());
+    }
+
+    #[test]
+    fn parses_empty_parts() {
+        let rule: Rule = "B3/".parse().unwrap();
+        assert_eq!(rule.get_birth(), vec![3]);
+        assert!(rule.get_survival().is_empty());
+        let rule: Rule = "/S23".parse().unwrap();
+        assert!(rule.get_birth().is_empty());
+        assert_eq!(rule.get_survival(), vec![2, 3]);
+        assert_eq!("B/S".parse::<Rule>(), Rule::new(&[], &[]));
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for text in ["B3/S23", "B36/S23", "B2/S", "B/S012345678", "B/S"] {
+            let rule: Rule = text.parse().unwrap();
+            assert_eq!(rule.to_string(), text);
+            assert_eq!(rule.to_string().parse::<Rule>(), Ok(rule));
+        }
+        assert_eq!("B3/".parse::<Rule>().unwrap().to_string(), "B3/S");
+    }
+
+    #[test]
+    fn rejects_invalid_rules() {
+        assert_eq!("".parse::<Rule>(), Err(RuleError::Empty));
+        assert_eq!("  ".parse::<Rule>(), Err(RuleError::Empty));
+        assert_eq!("B3S23".parse::<Rule>(), Err(RuleError::MissingSeparator));
+        assert_eq!("B39/S23".parse::<Rule>(), Err(RuleError::InvalidCount(9)));
+        assert_eq!("B3/S2x".parse::<Rule>(), Err(RuleError::InvalidCharacter('x')));
+        assert_eq!("B3/X23".parse::<Rule>(), Err(RuleError::InvalidCharacter('X')));
+        assert_eq!("B3/b6".parse::<Rule>(), Err(RuleError::DuplicatePart('B')));
+        assert_eq!(Rule::new(&[9], &[]), Err(RuleError::InvalidCount(9)));
+    }
+}
@@ -0,0 +1,92 @@
+/// run.rs
+/// - End of the runs: what to do when every cell died, and why a run ended.
+use std::fmt;
+
+use super::{Cycle, RunCondition};
+
+/// What to do when every cell died while running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExtinctionPolicy {
+    /// Stop the run, keeping the board and the generation.
+    #[default]
+    Stop,
+    /// Fill the board with a new random soup and start over from generation 0.
+    Reseed,
+    /// Keep running the empty board.
+    KeepRunning,
+}
+
+impl ExtinctionPolicy {
+    pub const ALL: [ExtinctionPolicy; 3] =
+        [ExtinctionPolicy::Stop, ExtinctionPolicy::Reseed, ExtinctionPolicy::KeepRunning];
+
+    /// Get the name of the policy.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExtinctionPolicy::Stop => "Stop on extinction",
+            ExtinctionPolicy::Reseed => "Reseed on extinction",
+            ExtinctionPolicy::KeepRunning => "Keep running",
+        }
+    }
+}
+
+/// Why a run ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EndReason {
+    /// Every cell died.
+    Extinct,
+    /// The board settled into the cycle.
+    Cycle(Cycle),
+    /// The condition of the run was met.
+    Condition(RunCondition),
+    /// The run was stopped by hand.
+    Stopped,
+}
+
+impl fmt::Display for EndReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EndReason::Extinct => write!(f, "died out"),
+            EndReason::Cycle(cycle) => write!(f, "{} found", cycle),
+            EndReason::Condition(condition) => write!(f, "reached {}", condition),
+            EndReason::Stopped => write!(f, "stopped"),
+        }
+    }
+}
+
+/// Event of the end of a run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RunEnd {
+    reason: EndReason,
+    generation: u64,
+    peak_population: usize,
+}
+
+impl RunEnd {
+    /// Create a new event of the run which ended at the generation.
+    pub fn new(reason: EndReason, generation: u64, peak_population: usize) -> RunEnd {
+        RunEnd { reason, generation, peak_population }
+    }
+    /// Get why the run ended.
+    pub fn get_reason(&self) -> EndReason {
+        self.reason
+    }
+    /// Get the last generation of the run.
+    pub fn get_generation(&self) -> u64 {
+        self.generation
+    }
+    /// Get the largest population during the run.
+    pub fn get_peak_population(&self) -> usize {
+        self.peak_population
+    }
+}
+
+impl fmt::Display for RunEnd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut reason = self.reason.to_string();
+        if let Some(first) = reason.get_mut(0..1) {
+            first.make_ascii_uppercase();
+        }
+        write!(f, "{} at generation {}, peak population {}", reason, self.generation, self.peak_population)
+    }
+}
@@ -0,0 +1,232 @@
+/// soup.rs
+/// - Random soups with a seedable generator and symmetries.
+use super::Transform;
+
+/// Pseudo random number generator (SplitMix64), reproducible from the seed.
+#[derive(Debug, Clone)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    /// Create a new generator from the seed.
+    pub fn new(seed: u64) -> Random {
+        Random { state: seed }
+    }
+    /// Get the next random number.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+    /// Get the next random number in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Symmetry of a soup around the center of its area.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Symmetry {
+    /// No symmetry.
+    #[default]
+    C1,
+    /// Rotation by 180 degrees.
+    C2,
+    /// Rotation by 90 degrees.
+    C4,
+    /// Reflection left and right.
+    D2,
+    /// Reflections left and right, and top and bottom.
+    D4,
+    /// Rotations by 90 degrees and reflections.
+    D8,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 6] =
+        [Symmetry::C1, Symmetry::C2, Symmetry::C4, Symmetry::D2, Symmetry::D4, Symmetry::D8];
+
+    /// Get the name of the symmetry.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Symmetry::C1 => "C1",
+            Symmetry::C2 => "C2",
+            Symmetry::C4 => "C4",
+            Symmetry::D2 => "D2",
+            Symmetry::D4 => "D4",
+            Symmetry::D8 => "D8",
+        }
+    }
+    /// Get the transforms which map the soup onto itself.
+    pub fn get_transforms(&self) -> &'static [Transform] {
+        match self {
+            Symmetry::C1 => &[Transform::Identity],
+            Symmetry::C2 => &[Transform::Identity, Transform::Rotate180],
+            Symmetry::C4 => &[Transform::Identity, Transform::Rotate90, Transform::Rotate180, Transform::Rotate270],
+            Symmetry::D2 => &[Transform::Identity, Transform::FlipHorizontal],
+            Symmetry::D4 => &[
+                Transform::Identity,
+                Transform::FlipHorizontal,
+                Transform::FlipVertical,
+                Transform::Rotate180,
+            ],
+            Symmetry::D8 => &Transform::ALL,
+        }
+    }
+    /// Check if the area of the soup must be square.
+    pub fn is_square(&self) -> bool {
+        matches!(self, Symmetry::C4 | Symmetry::D8)
+    }
+}
+
+/// Settings of a random soup.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Soup {
+    seed: u64,
+    density: f64,
+    symmetry: Symmetry,
+}
+
+impl Soup {
+    /// Create a new soup of the seed, with the density of 50% and no symmetry.
+    pub fn new(seed: u64) -> Soup {
+        Soup { seed, density: 0.5, symmetry: Symmetry::default() }
+    }
+    /// Get the seed of the random generator.
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+    /// Get the probability of a cell to be alive.
+    pub fn get_density(&self) -> f64 {
+        self.density
+    }
+    /// Set the probability of a cell to be alive, clamped into `[0, 1]`.
+    pub fn set_density(&mut self, density: f64) {
+        self.density = density.clamp(0.0, 1.0);
+    }
+    /// Get the symmetry of the soup.
+    pub fn get_symmetry(&self) -> Symmetry {
+        self.symmetry
+    }
+    /// Set the symmetry of the soup.
+    pub fn set_symmetry(&mut self, symmetry: Symmetry) {
+        self.symmetry = symmetry;
+    }
+    /// Generate the states of the cells in an area of `width` x `height`, row by row.
+    /// The same seed always generates the same soup.
+    pub fn generate(&self, width: usize, height: usize) -> Vec<bool> {
+        let mut random = Random::new(self.seed);
+        let mut cells = vec![false; width * height];
+        let (w, h) = (width as i32, height as i32);
+        for y in 0..h {
+            for x in 0..w {
+                // Coordinates doubled around the center, so that the transforms keep them integers.
+                let (u, v) = (2 * x - (w - 1), 2 * y - (h - 1));
+                let source = self
+                    .symmetry
+                    .get_transforms()
+                    .iter()
+                    .map(|transform| transform.apply(u, v))
+                    .map(|(u, v)| ((u + w - 1) / 2, (v + h - 1) / 2))
+                    .filter(|&(x, y)| (0..w).contains(&x) && (0..h).contains(&y))
+                    .min_by_key(|&(x, y)| (y, x))
+                    .unwrap_or((x, y));
+                cells[(y * w + x) as usize] = if source == (x, y) {
+                    random.next_f64() < self.density
+                } else {
+                    // The cell in the orbit which comes first was already generated.
+                    cells[(source.1 * w + source.0) as usize]
+                };
+            }
+        }
+        cells
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{BoundingBox, LifeGame, Universe};
+
+    fn soup(seed: u64, density: f64, symmetry: Symmetry) -> Soup {
+        let mut soup = Soup::new(seed);
+        soup.set_density(density);
+        soup.set_symmetry(symmetry);
+        soup
+    }
+
+    #[test]
+    fn random_is_deterministic_per_seed() {
+        let numbers = |seed: u64| {
+            let mut random = Random::new(seed);
+            (0..100).map(|_| random.next_u64()).collect::<Vec<_>>()
+        };
+        assert_eq!(numbers(42), numbers(42));
+        assert_ne!(numbers(42), numbers(43));
+        let mut random = Random::new(7);
+        assert!((0..1000).map(|_| random.next_f64()).all(|value| (0.0..1.0).contains(&value)));
+    }
+
+    #[test]
+    fn soup_is_deterministic_per_seed() {
+        for symmetry in Symmetry::ALL {
+            let cells = soup(1234, 0.4, symmetry).generate(16, 16);
+            assert_eq!(soup(1234, 0.4, symmetry).generate(16, 16), cells, "{:?}", symmetry);
+            assert_ne!(soup(1235, 0.4, symmetry).generate(16, 16), cells, "{:?}", symmetry);
+        }
+    }
+
+    #[test]
+    fn soup_follows_the_density() {
+        assert!(soup(1, 0.0, Symmetry::C1).generate(32, 32).iter().all(|&alive| !alive));
+        assert!(soup(1, 1.0, Symmetry::C1).generate(32, 32).iter().all(|&alive| alive));
+        let alive = soup(1, 0.3, Symmetry::C1).generate(100, 100).iter().filter(|&&alive| alive).count();
+        assert!((2700..3300).contains(&alive), "{}", alive);
+        assert_eq!(soup(1, 2.0, Symmetry::C1).get_density(), 1.0);
+    }
+
+    #[test]
+    fn soup_is_symmetric() {
+        for symmetry in Symmetry::ALL {
+            let sizes: &[(usize, usize)] =
+                if symmetry.is_square() { &[(7, 7), (8, 8)] } else { &[(7, 7), (8, 8), (9, 6)] };
+            for &(width, height) in sizes {
+                let cells = soup(99, 0.5, symmetry).generate(width, height);
+                let (w, h) = (width as i32, height as i32);
+                for y in 0..h {
+                    for x in 0..w {
+                        for transform in symmetry.get_transforms() {
+                            let (u, v) = transform.apply(2 * x - (w - 1), 2 * y - (h - 1));
+                            let (tx, ty) = ((u + w - 1) / 2, (v + h - 1) / 2);
+                            assert_eq!(
+                                cells[(y * w + x) as usize],
+                                cells[(ty * w + tx) as usize],
+                                "{:?} {}x{} ({}, {}) {:?}",
+                                symmetry,
+                                width,
+                                height,
+                                x,
+                                y,
+                                transform
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn fill_soup_crops_the_area_and_squares_it_for_the_rotations() {
+        let mut life_game = LifeGame::new(12, 6);
+        life_game.fill_soup(&soup(5, 1.0, Symmetry::C4), None);
+        assert_eq!(life_game.bounding_box(), Some(BoundingBox::new(3, 0, 8, 5)));
+        // The area is cropped to the board, and only the cells in it are cleared.
+        let mut toggled = life_game.fill_soup(&soup(5, 0.0, Symmetry::D2), Some(BoundingBox::new(-2, -2, 3, 3)));
+        toggled.sort_unstable();
+        assert_eq!(toggled, vec![(3, 0), (3, 1), (3, 2), (3, 3)]);
+    }
+}
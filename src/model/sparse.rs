@@ -0,0 +1,118 @@
+/// sparse.rs
+/// - Unbounded life-game which keeps only the alive cells.
+use std::collections::{HashMap, HashSet};
+
+use super::{BoundingBox, Rule, RuleError, Universe};
+
+/// SparseLife model
+/// The universe is an infinite plane, so it grows without bound.
+/// The coordinates are limited to i32, and the cells beyond them are always dead.
+#[derive(Debug, Clone, Default)]
+pub struct SparseLife {
+    cells: HashSet<(i32, i32)>,
+    generation: u64,
+    keep_alive: bool,
+    rule: Rule,
+}
+
+impl SparseLife {
+    /// Create a new empty universe.
+    pub fn new() -> SparseLife {
+        SparseLife::default()
+    }
+    /// Create a new universe with the alive cells.
+    pub fn from_cells<I: IntoIterator<Item = (i32, i32)>>(cells: I) -> SparseLife {
+        let cells: HashSet<(i32, i32)> = cells.into_iter().collect();
+        SparseLife {
+            keep_alive: !cells.is_empty(),
+            cells,
+            ..SparseLife::default()
+        }
+    }
+    /// Set the number of the generation, e.g. of a loaded pattern.
+    pub fn set_generation(&mut self, generation: u64) {
+        self.generation = generation;
+    }
+    /// Set the rule used by the following generations.
+    /// Rules with B0 would fill the infinite plane, so they are rejected.
+    pub fn set_rule(&mut self, rule: Rule) -> Result<(), RuleError> {
+        if rule.is_birth(0) {
+            return Err(RuleError::UnsupportedB0);
+        }
+        self.rule = rule;
+        Ok(())
+    }
+}
+
+impl Universe for SparseLife {
+    fn get_generation(&self) -> u64 {
+        self.generation
+    }
+    fn get_rule(&self) -> &Rule {
+        &self.rule
+    }
+    fn is_alive(&self, x: i32, y: i32) -> bool {
+        self.cells.contains(&(x, y))
+    }
+    fn set_alive(&mut self, x: i32, y: i32, alive: bool) {
+        if alive {
+            self.cells.insert((x, y));
+        } else {
+            self.cells.remove(&(x, y));
+        }
+    }
+    fn next_generation(&mut self) {
+        let mut neighbors: HashMap<(i32, i32), usize> = HashMap::with_capacity(self.cells.len() * 4);
+        for &(x, y) in &self.cells {
+            neighbors.entry((x, y)).or_insert(0);
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    if (dx != 0 || dy != 0)
+                        && let (Some(x), Some(y)) = (x.checked_add(dx), y.checked_add(dy))
+                    {
+                        *neighbors.entry((x, y)).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+        self.cells = neighbors
+            .into_iter()
+            .filter(|(position, count)| self.rule.next_state(self.cells.contains(position), *count))
+            .map(|(position, _)| position)
+            .collect();
+        self.keep_alive = !self.cells.is_empty();
+        self.generation += 1;
+    }
+    fn keep_alive(&self) -> bool {
+        self.keep_alive
+    }
+    fn reset(&mut self) {
+        self.cells.clear();
+        self.generation = 0;
+    }
+    fn population(&self) -> usize {
+        self.cells.len()
+    }
+    fn live_cells(&self) -> Vec<(i32, i32)> {
+        self.cells.iter().copied().collect()
+    }
+    fn bounding_box(&self) -> Option<BoundingBox> {
+        BoundingBox::from_cells(self.cells.iter().copied())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cells_beyond_i32_are_dead() {
+        // A blinker at the corner of the coordinates loses the cells which would be outside.
+        let (x, y) = (i32::MAX, i32::MIN);
+        let mut sparse = SparseLife::from_cells([(x - 2, y), (x - 1, y), (x, y)]);
+        sparse.next_generation();
+        let mut cells = sparse.live_cells();
+        cells.sort_unstable();
+        assert_eq!(cells, vec![(x - 1, y), (x - 1, y + 1)]);
+    }
+}
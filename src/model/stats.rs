@@ -0,0 +1,272 @@
+/// stats.rs
+/// - Statistics of the generations: population, births, deaths and the extent of the cells.
+use std::collections::VecDeque;
+use std::fmt::Write;
+
+use super::{BitGrid, BoundingBox};
+
+/// The number of generations kept by default.
+const DEFAULT_CAPACITY: usize = 10000;
+
+/// Header line of the statistics in CSV.
+pub const CSV_HEADER: &str = "generation,span,population,births,deaths,left,top,right,bottom,density,rate_of_change";
+
+/// Statistics of a generation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GenerationStats {
+    generation: u64,
+    span: u64,
+    population: usize,
+    births: usize,
+    deaths: usize,
+    bounding_box: Option<BoundingBox>,
+    density: f64,
+    rate_of_change: f64,
+}
+
+impl GenerationStats {
+    /// Get the statistics of the board `after` at `generation`,
+    /// which was computed from the board `before` at `before_generation`.
+    /// If generations were skipped, e.g. by HashLife, the births and the deaths are counted
+    /// between the two boards over the whole span of generations.
+    /// The births and the deaths are not counted if the boards have different sizes.
+    pub fn new(before: &BitGrid, before_generation: u64, after: &BitGrid, generation: u64) -> GenerationStats {
+        let population = after.count_alive();
+        let (mut births, mut deaths) = (0, 0);
+        if before.get_width() == after.get_width() && before.get_height() == after.get_height() {
+            for (&old, &new) in before.get_words().iter().zip(after.get_words()) {
+                births += (new & !old).count_ones() as usize;
+                deaths += (old & !new).count_ones() as usize;
+            }
+        }
+        let area = after.get_width() * after.get_height();
+        let span = generation.saturating_sub(before_generation);
+        GenerationStats {
+            generation,
+            span,
+            population,
+            births,
+            deaths,
+            bounding_box: after.bounding_box(),
+            density: if area == 0 { 0.0 } else { population as f64 / area as f64 },
+            rate_of_change: if span == 0 {
+                0.0
+            } else {
+                (population as f64 - before.count_alive() as f64) / span as f64
+            },
+        }
+    }
+    /// Get the statistics of the board without a previous generation.
+    pub fn of(cells: &BitGrid, generation: u64) -> GenerationStats {
+        GenerationStats::new(cells, generation, cells, generation)
+    }
+    /// Get the generation.
+    pub fn get_generation(&self) -> u64 {
+        self.generation
+    }
+    /// Get the number of generations since the previous board, or 0 without a previous board.
+    pub fn get_span(&self) -> u64 {
+        self.span
+    }
+    /// Get the number of alive cells.
+    pub fn get_population(&self) -> usize {
+        self.population
+    }
+    /// Get the number of cells which were born since the previous board.
+    pub fn get_births(&self) -> usize {
+        self.births
+    }
+    /// Get the number of cells which died since the previous board.
+    pub fn get_deaths(&self) -> usize {
+        self.deaths
+    }
+    /// Get the bounding box of the alive cells.
+    pub fn get_bounding_box(&self) -> Option<BoundingBox> {
+        self.bounding_box
+    }
+    /// Get the ratio of the alive cells to all the cells of the board.
+    pub fn get_density(&self) -> f64 {
+        self.density
+    }
+    /// Get the change of the population per generation since the previous board.
+    pub fn get_rate_of_change(&self) -> f64 {
+        self.rate_of_change
+    }
+    /// Get the statistics as a line of CSV, in the order of `CSV_HEADER`.
+    pub fn to_csv(&self) -> String {
+        let bounds = self.bounding_box.map_or(",,,".to_string(), |bounds| {
+            let ((left, top), (right, bottom)) = (bounds.get_min(), bounds.get_max());
+            format!("{},{},{},{}", left, top, right, bottom)
+        });
+        format!(
+            "{},{},{},{},{},{},{},{}",
+            self.generation,
+            self.span,
+            self.population,
+            self.births,
+            self.deaths,
+            bounds,
+            self.density,
+            self.rate_of_change
+        )
+    }
+}
+
+/// Statistics of the past generations in the order of the generation.
+/// The oldest generation is dropped when the capacity is exceeded.
+#[derive(Debug, Clone)]
+pub struct Statistics {
+    series: VecDeque<GenerationStats>,
+    capacity: usize,
+}
+
+impl Default for Statistics {
+    fn default() -> Statistics {
+        Statistics::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl Statistics {
+    /// Create new statistics keeping up to `capacity` generations.
+    pub fn new(capacity: usize) -> Statistics {
+        Statistics { series: VecDeque::new(), capacity }
+    }
+    /// Record the statistics of a generation.
+    /// The statistics of the same or later generations are dropped, e.g. after stepping back.
+    pub fn record(&mut self, stats: GenerationStats) {
+        while self.series.back().is_some_and(|last| last.generation >= stats.generation) {
+            self.series.pop_back();
+        }
+        self.series.push_back(stats);
+        while self.series.len() > self.capacity {
+            self.series.pop_front();
+        }
+    }
+    /// Get the statistics of the generations, from the oldest.
+    pub fn get_series(&self) -> impl DoubleEndedIterator<Item = &GenerationStats> + ExactSizeIterator {
+        self.series.iter()
+    }
+    /// Get the statistics of the latest generation.
+    pub fn get_latest(&self) -> Option<&GenerationStats> {
+        self.series.back()
+    }
+    /// Get the number of recorded generations.
+    pub fn len(&self) -> usize {
+        self.series.len()
+    }
+    /// Check if no generation is recorded.
+    pub fn is_empty(&self) -> bool {
+        self.series.is_empty()
+    }
+    /// Forget all the generations.
+    pub fn clear(&mut self) {
+        self.series.clear();
+    }
+    /// Get the statistics of all the generations in CSV with the header.
+    pub fn to_csv(&self) -> String {
+        let mut text = format!("{}\n", CSV_HEADER);
+        for stats in &self.series {
+            let _ = writeln!(text, "{}", stats.to_csv());
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(width: usize, height: usize, cells: &[(usize, usize)]) -> BitGrid {
+        let mut grid = BitGrid::new(width, height);
+        for &(x, y) in cells {
+            grid.set(x, y, true);
+        }
+        grid
+    }
+
+    fn generations(statistics: &Statistics) -> Vec<u64> {
+        statistics.get_series().map(|stats| stats.get_generation()).collect()
+    }
+
+    /// The two phases of a blinker on a board of 8 x 8.
+    fn blinker() -> (BitGrid, BitGrid) {
+        (grid(8, 8, &[(2, 3), (3, 3), (4, 3)]), grid(8, 8, &[(3, 2), (3, 3), (3, 4)]))
+    }
+
+    #[test]
+    fn counts_the_births_and_the_deaths() {
+        let (before, after) = blinker();
+        let stats = GenerationStats::new(&before, 1, &after, 2);
+        assert_eq!(stats.get_generation(), 2);
+        assert_eq!(stats.get_span(), 1);
+        assert_eq!(stats.get_population(), 3);
+        assert_eq!((stats.get_births(), stats.get_deaths()), (2, 2));
+        assert_eq!(stats.get_bounding_box(), Some(BoundingBox::new(3, 2, 3, 4)));
+        assert_eq!(stats.get_density(), 3.0 / 64.0);
+        assert_eq!(stats.get_rate_of_change(), 0.0);
+    }
+
+    #[test]
+    fn rate_of_change_is_per_generation_over_the_span() {
+        let before = grid(8, 8, &[(0, 0)]);
+        let after = grid(8, 8, &[(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (5, 0), (6, 0)]);
+        let stats = GenerationStats::new(&before, 10, &after, 13);
+        assert_eq!(stats.get_span(), 3);
+        assert_eq!((stats.get_births(), stats.get_deaths()), (6, 0));
+        assert_eq!(stats.get_rate_of_change(), 2.0);
+        let stats = GenerationStats::new(&after, 13, &before, 15);
+        assert_eq!((stats.get_births(), stats.get_deaths()), (0, 6));
+        assert_eq!(stats.get_rate_of_change(), -3.0);
+    }
+
+    #[test]
+    fn skips_the_births_and_the_deaths_between_boards_of_other_sizes() {
+        let (before, _) = blinker();
+        let stats = GenerationStats::new(&before, 0, &grid(4, 4, &[(0, 0)]), 1);
+        assert_eq!((stats.get_births(), stats.get_deaths()), (0, 0));
+        assert_eq!(stats.get_population(), 1);
+        assert_eq!(stats.get_density(), 1.0 / 16.0);
+    }
+
+    #[test]
+    fn stats_of_a_board_have_no_change() {
+        let (cells, _) = blinker();
+        let stats = GenerationStats::of(&cells, 5);
+        assert_eq!((stats.get_span(), stats.get_births(), stats.get_deaths()), (0, 0, 0));
+        assert_eq!(stats.get_rate_of_change(), 0.0);
+        let empty = GenerationStats::of(&BitGrid::new(0, 0), 0);
+        assert_eq!(empty.get_bounding_box(), None);
+        assert_eq!(empty.get_density(), 0.0);
+    }
+
+    #[test]
+    fn writes_the_csv_rows_in_the_order_of_the_header() {
+        let (before, after) = blinker();
+        let row = GenerationStats::new(&before, 1, &after, 2).to_csv();
+        assert_eq!(row, "2,1,3,2,2,3,2,3,4,0.046875,0");
+        let empty = GenerationStats::of(&BitGrid::new(8, 8), 0).to_csv();
+        assert_eq!(empty, "0,0,0,0,0,,,,,0,0");
+        let columns = CSV_HEADER.split(',').count();
+        assert_eq!(row.split(',').count(), columns);
+        assert_eq!(empty.split(',').count(), columns);
+    }
+
+    #[test]
+    fn statistics_drop_the_later_and_the_oldest_generations() {
+        let (before, after) = blinker();
+        let mut statistics = Statistics::new(3);
+        for generation in 0..5 {
+            let cells = if generation % 2 == 0 { &before } else { &after };
+            statistics.record(GenerationStats::of(cells, generation));
+        }
+        assert_eq!(generations(&statistics), [2, 3, 4]);
+        statistics.record(GenerationStats::of(&before, 3));
+        assert_eq!(generations(&statistics), [2, 3]);
+        assert_eq!(statistics.get_latest().map(|stats| stats.get_generation()), Some(3));
+        let csv = statistics.to_csv();
+        assert_eq!(csv.lines().next(), Some(CSV_HEADER));
+        assert_eq!(csv.lines().count(), 3);
+        statistics.clear();
+        assert!(statistics.is_empty());
+    }
+}
@@ -0,0 +1,159 @@
+/// timeline.rs
+/// - Ring buffer of the snapshots of the past generations.
+use std::collections::VecDeque;
+
+use super::Snapshot;
+
+/// The number of snapshots kept by default.
+const DEFAULT_CAPACITY: usize = 500;
+
+/// Snapshots of the past generations in the order of the generation.
+/// The oldest snapshot is dropped when the capacity is exceeded.
+#[derive(Debug, Clone)]
+pub struct Timeline {
+    snapshots: VecDeque<Snapshot>,
+    capacity: usize,
+}
+
+impl Default for Timeline {
+    fn default() -> Timeline {
+        Timeline::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl Timeline {
+    /// Create a new timeline keeping up to `capacity` snapshots.
+    pub fn new(capacity: usize) -> Timeline {
+        Timeline { snapshots: VecDeque::new(), capacity }
+    }
+    /// Get the number of snapshots to keep.
+    pub fn get_capacity(&self) -> usize {
+        self.capacity
+    }
+    /// Set the number of snapshots to keep, dropping the oldest ones which do not fit.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.trim();
+    }
+    /// Record the snapshot. The snapshots of the same or later generations are dropped,
+    /// since the board went back in time or was edited.
+    pub fn record(&mut self, snapshot: Snapshot) {
+        while self
+            .snapshots
+            .back()
+            .is_some_and(|last| last.get_generation() >= snapshot.get_generation())
+        {
+            self.snapshots.pop_back();
+        }
+        self.snapshots.push_back(snapshot);
+        self.trim();
+    }
+    fn trim(&mut self) {
+        while self.snapshots.len() > self.capacity {
+            self.snapshots.pop_front();
+        }
+    }
+    /// Get the number of the snapshots.
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+    /// Check if there is no snapshot.
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+    /// Get the snapshot at the index, from the oldest one.
+    pub fn get(&self, index: usize) -> Option<&Snapshot> {
+        self.snapshots.get(index)
+    }
+    /// Get the index of the latest snapshot at or before the generation.
+    pub fn find(&self, generation: u64) -> Option<usize> {
+        let index = self.snapshots.partition_point(|snapshot| snapshot.get_generation() <= generation);
+        index.checked_sub(1)
+    }
+    /// Get the memory used by the compressed snapshots in bytes.
+    pub fn get_compressed_size(&self) -> usize {
+        self.snapshots.iter().map(|snapshot| snapshot.get_compressed_size()).sum()
+    }
+    /// Drop all the snapshots.
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{LifeGame, Universe};
+
+    /// Record the generations of a blinker from generation 0 to `last`.
+    fn record_blinker(timeline: &mut Timeline, last: u64) -> LifeGame {
+        let mut life_game = LifeGame::new(8, 8);
+        for x in 2..5 {
+            life_game.set_alive(x, 3, true);
+        }
+        timeline.record(life_game.snapshot());
+        while life_game.get_generation() < last {
+            life_game.next_generation();
+            timeline.record(life_game.snapshot());
+        }
+        life_game
+    }
+
+    fn generations(timeline: &Timeline) -> Vec<u64> {
+        (0..timeline.len()).map(|index| timeline.get(index).unwrap().get_generation()).collect()
+    }
+
+    #[test]
+    fn drops_the_oldest_snapshots_beyond_the_capacity() {
+        let mut timeline = Timeline::new(3);
+        record_blinker(&mut timeline, 5);
+        assert_eq!(generations(&timeline), [3, 4, 5]);
+        timeline.set_capacity(2);
+        assert_eq!(generations(&timeline), [4, 5]);
+        assert_eq!(timeline.get_capacity(), 2);
+    }
+
+    #[test]
+    fn seeks_the_latest_snapshot_at_or_before_the_generation() {
+        let mut timeline = Timeline::new(10);
+        assert_eq!(timeline.find(0), None);
+        record_blinker(&mut timeline, 9);
+        let mut sparse = Timeline::new(10);
+        for index in [2, 4, 6] {
+            sparse.record(timeline.get(index).unwrap().clone());
+        }
+        assert_eq!(sparse.find(1), None);
+        assert_eq!(sparse.find(2), Some(0));
+        assert_eq!(sparse.find(5), Some(1));
+        assert_eq!(sparse.find(100), Some(2));
+
+        // Restoring a snapshot brings back the board of its generation.
+        let mut life_game = LifeGame::new(1, 1);
+        life_game.restore(timeline.get(timeline.find(3).unwrap()).unwrap());
+        assert_eq!(life_game.get_generation(), 3);
+        assert_eq!(life_game.live_cells(), vec![(3, 2), (3, 3), (3, 4)]);
+    }
+
+    #[test]
+    fn truncates_the_later_snapshots_when_the_board_goes_back() {
+        let mut timeline = Timeline::new(10);
+        let mut life_game = record_blinker(&mut timeline, 6);
+        life_game.restore(timeline.get(2).unwrap());
+        life_game.set_alive(0, 0, true);
+        timeline.record(life_game.snapshot());
+        assert_eq!(generations(&timeline), [0, 1, 2]);
+        assert_eq!(timeline.get(2), Some(&life_game.snapshot()));
+    }
+
+    #[test]
+    fn sums_the_compressed_sizes() {
+        let mut timeline = Timeline::new(10);
+        record_blinker(&mut timeline, 3);
+        let size: usize = (0..timeline.len()).map(|index| timeline.get(index).unwrap().get_compressed_size()).sum();
+        assert_eq!(timeline.get_compressed_size(), size);
+        assert!(size > 0);
+        timeline.clear();
+        assert!(timeline.is_empty());
+        assert_eq!(timeline.get_compressed_size(), 0);
+    }
+}
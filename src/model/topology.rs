@@ -0,0 +1,182 @@
+/// topology.rs
+/// - Topology of the board edges.
+use std::fmt;
+
+/// How the edges of the board are connected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Topology {
+    /// Both axes wrap around.
+    #[default]
+    Torus,
+    /// Cells outside the board are always dead.
+    Plane,
+    /// The x axis wraps around, the top and bottom are dead borders.
+    Cylinder,
+    /// The x axis wraps around, the y axis wraps with the columns mirrored.
+    KleinBottle,
+    /// Both axes wrap with the other axis mirrored (real projective plane).
+    CrossSurface,
+}
+
+impl Topology {
+    /// All the topologies.
+    pub const ALL: [Topology; 5] = [
+        Topology::Torus,
+        Topology::Plane,
+        Topology::Cylinder,
+        Topology::KleinBottle,
+        Topology::CrossSurface,
+    ];
+
+    /// Get the name of the topology.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Topology::Torus => "Torus",
+            Topology::Plane => "Plane",
+            Topology::Cylinder => "Cylinder",
+            Topology::KleinBottle => "Klein bottle",
+            Topology::CrossSurface => "Cross-surface",
+        }
+    }
+
+    /// Find the topology by its name, ignoring case and treating `-` and `_` as spaces.
+    pub fn from_name(name: &str) -> Option<Topology> {
+        let normalize = |name: &str| name.trim().to_lowercase().replace(['-', '_'], " ");
+        let name = normalize(name);
+        Topology::ALL.into_iter().find(|topology| normalize(topology.name()) == name)
+    }
+
+    /// Map the position onto the board of `width` x `height`.
+    /// Returns `None` if the position is outside of the board and does not wrap.
+    pub fn map(&self, x: i32, y: i32, width: usize, height: usize) -> Option<(i32, i32)> {
+        let (w, h) = (width as i32, height as i32);
+        if w == 0 || h == 0 {
+            return None;
+        }
+        let inside_x = (0..w).contains(&x);
+        let inside_y = (0..h).contains(&y);
+        let flip_x = y.div_euclid(h) % 2 != 0;
+        let flip_y = x.div_euclid(w) % 2 != 0;
+        let (wrap_x, wrap_y) = (x.rem_euclid(w), y.rem_euclid(h));
+        match self {
+            Topology::Torus => Some((wrap_x, wrap_y)),
+            Topology::Plane => (inside_x && inside_y).then_some((x, y)),
+            Topology::Cylinder => inside_y.then_some((wrap_x, y)),
+            Topology::KleinBottle => {
+                let x = if flip_x { w - 1 - wrap_x } else { wrap_x };
+                Some((x, wrap_y))
+            }
+            Topology::CrossSurface => {
+                let x = if flip_x { w - 1 - wrap_x } else { wrap_x };
+                let y = if flip_y { h - 1 - wrap_y } else { wrap_y };
+                Some((x, y))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Topology {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIDTH: usize = 4;
+    const HEIGHT: usize = 3;
+
+    fn map(topology: Topology, x: i32, y: i32) -> Option<(i32, i32)> {
+        topology.map(x, y, WIDTH, HEIGHT)
+    }
+
+    #[test]
+    fn keeps_the_positions_on_the_board() {
+        for topology in Topology::ALL {
+            for y in 0..HEIGHT as i32 {
+                for x in 0..WIDTH as i32 {
+                    assert_eq!(map(topology, x, y), Some((x, y)), "{:?}", topology);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn maps_the_edges_of_torus_plane_and_cylinder() {
+        assert_eq!(map(Topology::Torus, -1, 1), Some((3, 1)));
+        assert_eq!(map(Topology::Torus, 1, 3), Some((1, 0)));
+        assert_eq!(map(Topology::Torus, -1, -1), Some((3, 2)));
+        assert_eq!(map(Topology::Plane, -1, 1), None);
+        assert_eq!(map(Topology::Plane, 4, 2), None);
+        assert_eq!(map(Topology::Cylinder, -1, 1), Some((3, 1)));
+        assert_eq!(map(Topology::Cylinder, 4, 0), Some((0, 0)));
+        assert_eq!(map(Topology::Cylinder, 1, -1), None);
+        assert_eq!(map(Topology::Cylinder, 4, 3), None);
+    }
+
+    #[test]
+    fn maps_the_edges_and_corners_of_klein_bottle() {
+        // The left and right edges join as they are.
+        assert_eq!(map(Topology::KleinBottle, -1, 0), Some((3, 0)));
+        assert_eq!(map(Topology::KleinBottle, 4, 2), Some((0, 2)));
+        // The top and bottom edges join with the columns mirrored.
+        assert_eq!(map(Topology::KleinBottle, 0, -1), Some((3, 2)));
+        assert_eq!(map(Topology::KleinBottle, 1, 3), Some((2, 0)));
+        // Two boards away, the columns are mirrored twice.
+        assert_eq!(map(Topology::KleinBottle, 1, 6), Some((1, 0)));
+        assert_eq!(map(Topology::KleinBottle, -1, -1), Some((0, 2)));
+        assert_eq!(map(Topology::KleinBottle, 4, -1), Some((3, 2)));
+        assert_eq!(map(Topology::KleinBottle, -1, 3), Some((0, 0)));
+        assert_eq!(map(Topology::KleinBottle, 4, 3), Some((3, 0)));
+    }
+
+    #[test]
+    fn maps_the_edges_and_corners_of_cross_surface() {
+        // The left and right edges join with the rows mirrored.
+        assert_eq!(map(Topology::CrossSurface, -1, 0), Some((3, 2)));
+        assert_eq!(map(Topology::CrossSurface, 4, 2), Some((0, 0)));
+        // The top and bottom edges join with the columns mirrored.
+        assert_eq!(map(Topology::CrossSurface, 0, -1), Some((3, 2)));
+        assert_eq!(map(Topology::CrossSurface, 1, 3), Some((2, 0)));
+        // Beyond a corner, both axes are mirrored.
+        assert_eq!(map(Topology::CrossSurface, -1, -1), Some((0, 0)));
+        assert_eq!(map(Topology::CrossSurface, 4, -1), Some((3, 0)));
+        assert_eq!(map(Topology::CrossSurface, -1, 3), Some((0, 2)));
+        assert_eq!(map(Topology::CrossSurface, 4, 3), Some((3, 2)));
+    }
+
+    #[test]
+    fn wraps_the_neighbors_of_the_edges_onto_the_board() {
+        for topology in Topology::ALL {
+            for y in -1..=HEIGHT as i32 {
+                for x in -1..=WIDTH as i32 {
+                    let inside = (0..WIDTH as i32).contains(&x) && (0..HEIGHT as i32).contains(&y);
+                    match map(topology, x, y) {
+                        Some((x, y)) => assert!((0..WIDTH as i32).contains(&x) && (0..HEIGHT as i32).contains(&y)),
+                        None => assert!(!inside && matches!(topology, Topology::Plane | Topology::Cylinder)),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn maps_nothing_on_an_empty_board() {
+        for topology in Topology::ALL {
+            assert_eq!(topology.map(0, 0, 0, 3), None);
+            assert_eq!(topology.map(0, 0, 4, 0), None);
+        }
+    }
+
+    #[test]
+    fn finds_the_names() {
+        for topology in Topology::ALL {
+            assert_eq!(Topology::from_name(topology.name()), Some(topology));
+        }
+        assert_eq!(Topology::from_name("klein-bottle"), Some(Topology::KleinBottle));
+        assert_eq!(Topology::from_name("CROSS_SURFACE"), Some(Topology::CrossSurface));
+        assert_eq!(Topology::from_name("sphere"), None);
+    }
+}
@@ -3,31 +3,164 @@
 use gtk::prelude::*;
 use relm4::{
     gtk,
-    factory::FactoryVecDeque,
+    Component,
+    ComponentController,
     ComponentSender,
     ComponentParts,
+    Controller,
     SimpleComponent,
 };
-use tokio::time::{self, Duration};
+use tokio::time::{self, Duration, MissedTickBehavior};
 use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 use tokio::sync::Notify;
 use tokio::select;
 
-use crate::model::{LifeGame, Pattern};
-use crate::component::{CellModel, CellMsg, CellOutputMsg};
+use crate::model::{Anchor, BitGrid, BoundingBox, Census, Command, Cycle, CycleDetector, Engine, EndReason, ExtinctionPolicy, GenerationStats, HashLife, History, LifeGame, PasteMode, Random, Rule, RunCondition, RunEnd, RunUntil, Snapshot, Soup, SparseLife, Statistics, Storage, Symmetry, Timeline, Topology, Transform, Universe, MAX_BOARD_SIZE};
+use crate::format::{self, Format, FormatError, PatternData};
+use crate::library::PatternLibrary;
+use crate::component::{BoardModel, BoardMsg, BoardOutputMsg, ChartModel, ChartMsg};
 
 pub struct ViewModel {
+    window: gtk::Window,
+    rule_entry: gtk::Entry,
     life_game: LifeGame,
-    cell_widgets: FactoryVecDeque<CellModel>,
+    board: Controller<BoardModel>,
     timer: bool,
     timer_handle: Option<Arc<Notify>>,
+    rule_error: Option<String>,
+    engine: Engine,
+    hash_life: Option<HashLife>,
+    sparse_life: Option<SparseLife>,
+    hash_step: u8,
+    resize_width: usize,
+    resize_height: usize,
+    resize_anchor: Anchor,
+    file_error: Option<String>,
+    save_format: Format,
+    library: PatternLibrary,
+    pattern_list: gtk::ListBox,
+    pattern_query: String,
+    pattern_matches: Vec<usize>,
+    library_error: Option<String>,
+    stamp_mode: bool,
+    stamp_pattern: Option<PatternData>,
+    stamp_transform: Transform,
+    paste_mode: PasteMode,
+    history: History,
+    timeline: Timeline,
+    cycle_detector: CycleDetector,
+    cycle: Option<Cycle>,
+    stop_on_cycle: bool,
+    census: Option<Census>,
+    soup: Soup,
+    soup_size: usize,
+    seed_entry: gtk::Entry,
+    soup_error: Option<String>,
+    speed: usize,
+    generations_per_frame: usize,
+    last_render: Instant,
+    advance_count: u64,
+    run_condition: RunCondition,
+    run_value: u64,
+    run_until: Option<RunUntil>,
+    run_message: Option<String>,
+    extinction_policy: ExtinctionPolicy,
+    peak_population: usize,
+    statistics: Statistics,
+    /// The cells and the snapshot of the latest recorded generation, which start the next steps.
+    recorded: Option<(BitGrid, Snapshot)>,
+    chart: Controller<ChartModel>,
 }
 
+/// Delays between the frames of the simulation, from the slowest to as fast as possible.
+const SPEEDS: [Duration; 12] = [
+    Duration::from_secs(5),
+    Duration::from_secs(3),
+    Duration::from_secs(2),
+    Duration::from_secs(1),
+    Duration::from_millis(500),
+    Duration::from_millis(250),
+    Duration::from_millis(100),
+    Duration::from_millis(50),
+    Duration::from_millis(20),
+    Duration::from_millis(10),
+    Duration::from_millis(1),
+    Duration::ZERO,
+];
+/// The speed at the start, one generation per second.
+const DEFAULT_SPEED: usize = 3;
+/// The shortest time between the drawings of the board. Faster frames are not drawn.
+const RENDER_INTERVAL: Duration = Duration::from_millis(33);
+/// The largest number of generations per frame.
+const MAX_GENERATIONS_PER_FRAME: usize = 10000;
+
+/// The largest number of generations to advance, and of the values of the run conditions.
+const MAX_RUN_VALUE: u64 = 1_000_000_000;
+
+/// The number of the latest generations shown in the population chart.
+const CHART_LENGTH: usize = 500;
+
+/// The number of generations to group the objects by the census, if no cycle is found.
+const CENSUS_PHASES: u32 = 4;
+/// The largest number of generations to group the objects by the census.
+const MAX_CENSUS_PHASES: u32 = 60;
+
 #[derive(Debug)]
 pub enum LifeGameMsg {
     StartStop,
-    NextGeneration,
-    SelectPattern(Pattern),
+    Step,
+    Advance,
+    SetAdvanceCount(u64),
+    SetRunCondition(RunCondition),
+    SetRunValue(u64),
+    RunUntil,
+    SetExtinctionPolicy(ExtinctionPolicy),
+    /// The run ended, e.g. every cell died or the condition was met.
+    RunEnded(RunEnd),
+    /// Advance the generations of a frame while running.
+    Tick,
+    SetSpeed(usize),
+    SetGenerationsPerFrame(usize),
+    StepBack,
+    Undo,
+    Redo,
+    Clear,
+    SetStopOnCycle(bool),
+    TakeCensus,
+    ExportStatistics,
+    ExportStatisticsToFile(PathBuf),
+    SetSoupDensity(f64),
+    SetSoupSymmetry(Symmetry),
+    SetSoupSize(usize),
+    NewSoup,
+    FillSoup,
+    ScrubTimeline(usize),
+    SetTimelineCapacity(usize),
+    SelectPattern(usize),
+    SearchPatterns(String),
+    OpenLibrary,
+    LoadLibrary(PathBuf),
+    SetStampMode(bool),
+    SetStampTransform(Transform),
+    SetPasteMode(PasteMode),
+    Stamp { column: i32, row: i32 },
+    SetRule(String),
+    SetTopology(Topology),
+    SetEngine(Engine),
+    SetHashStep(u8),
+    FitPattern,
+    CenterOrigin,
+    SetResizeWidth(usize),
+    SetResizeHeight(usize),
+    SetResizeAnchor(Anchor),
+    Resize,
+    OpenFile,
+    LoadFile(PathBuf),
+    SetSaveFormat(Format),
+    SaveFile,
+    SaveToFile(PathBuf),
     StateChanged { column: i32, row: i32, alive: bool },
 }
 
@@ -49,38 +182,465 @@ impl SimpleComponent for ViewModel {
                     set_orientation: gtk::Orientation::Horizontal,
                     set_spacing: 5,
                     gtk::Button {
-                        set_label: "Blinker",
-                        connect_clicked => LifeGameMsg::SelectPattern(Pattern::Blinker),
+                        set_label: "Open...",
+                        connect_clicked => LifeGameMsg::OpenFile,
                     },
                     gtk::Button {
-                        set_label: "Toad",
-                        connect_clicked => LifeGameMsg::SelectPattern(Pattern::Toad),
+                        set_label: "Save...",
+                        connect_clicked => LifeGameMsg::SaveFile,
+                    },
+                    gtk::DropDown::from_strings(&Format::ALL.map(|format| format.name())) {
+                        connect_selected_notify[sender] => move |dropdown| {
+                            if let Some(format) = Format::ALL.get(dropdown.selected() as usize) {
+                                sender.input(LifeGameMsg::SetSaveFormat(*format));
+                            }
+                        },
+                    },
+                    gtk::Label {
+                        #[watch]
+                        set_label: match &model.file_error {
+                            Some(error) => error,
+                            None => "",
+                        },
+                    },
+                },
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Horizontal,
+                    set_spacing: 5,
+                    gtk::SearchEntry {
+                        set_placeholder_text: Some("Search patterns"),
+                        connect_search_changed[sender] => move |entry| {
+                            sender.input(LifeGameMsg::SearchPatterns(entry.text().to_string()));
+                        },
                     },
                     gtk::Button {
-                        set_label: "Glider",
-                        connect_clicked => LifeGameMsg::SelectPattern(Pattern::Glider),
+                        set_label: "Library...",
+                        connect_clicked => LifeGameMsg::OpenLibrary,
+                    },
+                    gtk::ToggleButton {
+                        set_label: "Stamp",
+                        set_tooltip_text: Some("Place the selected pattern by clicking the board"),
+                        #[watch]
+                        set_active: model.stamp_mode,
+                        connect_toggled[sender] => move |button| {
+                            sender.input(LifeGameMsg::SetStampMode(button.is_active()));
+                        },
+                    },
+                    gtk::DropDown::from_strings(&Transform::ALL.map(|transform| transform.name())) {
+                        connect_selected_notify[sender] => move |dropdown| {
+                            if let Some(transform) = Transform::ALL.get(dropdown.selected() as usize) {
+                                sender.input(LifeGameMsg::SetStampTransform(*transform));
+                            }
+                        },
+                    },
+                    gtk::DropDown::from_strings(&PasteMode::ALL.map(|mode| mode.name())) {
+                        connect_selected_notify[sender] => move |dropdown| {
+                            if let Some(mode) = PasteMode::ALL.get(dropdown.selected() as usize) {
+                                sender.input(LifeGameMsg::SetPasteMode(*mode));
+                            }
+                        },
+                    },
+                    gtk::Label {
+                        #[watch]
+                        set_label: match &model.library_error {
+                            Some(error) => error,
+                            None => "",
+                        },
+                    },
+                },
+                gtk::ScrolledWindow {
+                    set_min_content_height: 100,
+                    set_hscrollbar_policy: gtk::PolicyType::Never,
+                    #[local_ref]
+                    pattern_list -> gtk::ListBox {
+                        connect_row_activated[sender] => move |_, row| {
+                            sender.input(LifeGameMsg::SelectPattern(row.index() as usize));
+                        },
                     },
+                },
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Horizontal,
+                    set_spacing: 5,
                     gtk::Button {
-                        set_label: "Beacon",
-                        connect_clicked => LifeGameMsg::SelectPattern(Pattern::Beacon),
+                        set_label: "Random soup",
+                        set_tooltip_text: Some("Fill the board with a soup of a new seed"),
+                        connect_clicked => LifeGameMsg::NewSoup,
+                    },
+                    gtk::Label {
+                        set_label: "Seed",
+                    },
+                    #[local_ref]
+                    seed_entry -> gtk::Entry {
+                        set_text: &model.soup.get_seed().to_string(),
+                        set_tooltip_text: Some("Press Enter to fill the board with the soup of this seed"),
+                        connect_activate => LifeGameMsg::FillSoup,
+                    },
+                    gtk::Label {
+                        set_label: "Density %",
+                    },
+                    gtk::SpinButton::with_range(0.0, 100.0, 1.0) {
+                        set_value: model.soup.get_density() * 100.0,
+                        connect_value_changed[sender] => move |spin| {
+                            sender.input(LifeGameMsg::SetSoupDensity(spin.value() / 100.0));
+                        },
+                    },
+                    gtk::DropDown::from_strings(&Symmetry::ALL.map(|symmetry| symmetry.name())) {
+                        connect_selected_notify[sender] => move |dropdown| {
+                            if let Some(symmetry) = Symmetry::ALL.get(dropdown.selected() as usize) {
+                                sender.input(LifeGameMsg::SetSoupSymmetry(*symmetry));
+                            }
+                        },
+                    },
+                    gtk::Label {
+                        set_label: "Area",
+                    },
+                    gtk::SpinButton::with_range(0.0, MAX_BOARD_SIZE as f64, 1.0) {
+                        set_tooltip_text: Some("Side of the centered square to fill, or 0 for the whole board"),
+                        connect_value_changed[sender] => move |spin| {
+                            sender.input(LifeGameMsg::SetSoupSize(spin.value() as usize));
+                        },
+                    },
+                    gtk::Label {
+                        #[watch]
+                        set_label: match &model.soup_error {
+                            Some(error) => error,
+                            None => "",
+                        },
                     },
                 },
-                #[name(start_stop_button)]
-                gtk::Button {
-                    #[watch]
-                    set_label: if model.timer { "Stop" } else { "Start" },
-                    connect_clicked => LifeGameMsg::StartStop,
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Horizontal,
+                    set_spacing: 5,
+                    gtk::Label {
+                        set_label: "Rule",
+                    },
+                    #[local_ref]
+                    rule_entry -> gtk::Entry {
+                        set_text: &model.life_game.get_rule().to_string(),
+                        set_placeholder_text: Some("B3/S23"),
+                        connect_activate[sender] => move |entry| {
+                            sender.input(LifeGameMsg::SetRule(entry.text().to_string()));
+                        },
+                    },
+                    gtk::Label {
+                        #[watch]
+                        set_label: match &model.rule_error {
+                            Some(error) => error,
+                            None => "",
+                        },
+                    },
+                    gtk::Label {
+                        set_label: "Topology",
+                    },
+                    gtk::DropDown::from_strings(&Topology::ALL.map(|topology| topology.name())) {
+                        connect_selected_notify[sender] => move |dropdown| {
+                            if let Some(topology) = Topology::ALL.get(dropdown.selected() as usize) {
+                                sender.input(LifeGameMsg::SetTopology(*topology));
+                            }
+                        },
+                    },
+                },
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Horizontal,
+                    set_spacing: 5,
+                    gtk::Label {
+                        set_label: "Engine",
+                    },
+                    gtk::DropDown::from_strings(&Engine::ALL.map(|engine| engine.name())) {
+                        connect_selected_notify[sender] => move |dropdown| {
+                            if let Some(engine) = Engine::ALL.get(dropdown.selected() as usize) {
+                                sender.input(LifeGameMsg::SetEngine(*engine));
+                            }
+                        },
+                    },
+                    gtk::Label {
+                        set_label: "Step 2^",
+                    },
+                    gtk::SpinButton::with_range(0.0, 30.0, 1.0) {
+                        #[watch]
+                        set_sensitive: model.get_engine() == Engine::HashLife,
+                        connect_value_changed[sender] => move |spin| {
+                            sender.input(LifeGameMsg::SetHashStep(spin.value() as u8));
+                        },
+                    },
+                    gtk::Label {
+                        #[watch]
+                        set_label: &engine_text(model.engine, model.life_game.get_topology()),
+                    },
+                },
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Horizontal,
+                    set_spacing: 5,
+                    #[name(start_stop_button)]
+                    gtk::Button {
+                        set_hexpand: true,
+                        #[watch]
+                        set_label: if model.timer { "Stop" } else { "Start" },
+                        connect_clicked => LifeGameMsg::StartStop,
+                    },
+                    gtk::Button {
+                        set_label: "Step",
+                        set_tooltip_text: Some("Advance a generation"),
+                        connect_clicked => LifeGameMsg::Step,
+                    },
+                    gtk::Button {
+                        set_label: "Advance",
+                        set_tooltip_text: Some("Advance the number of generations as fast as possible"),
+                        connect_clicked => LifeGameMsg::Advance,
+                    },
+                    gtk::SpinButton::with_range(1.0, MAX_RUN_VALUE as f64, 1.0) {
+                        set_value: model.advance_count as f64,
+                        connect_value_changed[sender] => move |spin| {
+                            sender.input(LifeGameMsg::SetAdvanceCount(spin.value() as u64));
+                        },
+                    },
+                },
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Horizontal,
+                    set_spacing: 5,
+                    gtk::Button {
+                        set_label: "Run until",
+                        set_tooltip_text: Some("Run as fast as possible until the condition is met"),
+                        connect_clicked => LifeGameMsg::RunUntil,
+                    },
+                    gtk::DropDown::from_strings(&RunCondition::ALL.map(|condition| condition.name())) {
+                        set_selected: RunCondition::ALL
+                            .iter()
+                            .position(|condition| condition.name() == model.run_condition.name())
+                            .unwrap_or_default() as u32,
+                        connect_selected_notify[sender] => move |dropdown| {
+                            if let Some(condition) = RunCondition::ALL.get(dropdown.selected() as usize) {
+                                sender.input(LifeGameMsg::SetRunCondition(*condition));
+                            }
+                        },
+                    },
+                    gtk::SpinButton::with_range(0.0, MAX_RUN_VALUE as f64, 1.0) {
+                        #[watch]
+                        set_sensitive: model.run_condition.get_value().is_some(),
+                        connect_value_changed[sender] => move |spin| {
+                            sender.input(LifeGameMsg::SetRunValue(spin.value() as u64));
+                        },
+                    },
+                    gtk::DropDown::from_strings(&ExtinctionPolicy::ALL.map(|policy| policy.name())) {
+                        set_tooltip_text: Some("What to do when every cell died while running"),
+                        connect_selected_notify[sender] => move |dropdown| {
+                            if let Some(policy) = ExtinctionPolicy::ALL.get(dropdown.selected() as usize) {
+                                sender.input(LifeGameMsg::SetExtinctionPolicy(*policy));
+                            }
+                        },
+                    },
+                    gtk::Label {
+                        #[watch]
+                        set_label: match &model.run_message {
+                            Some(message) => message,
+                            None => "",
+                        },
+                    },
+                },
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Horizontal,
+                    set_spacing: 5,
+                    gtk::Label {
+                        set_label: "Speed",
+                    },
+                    gtk::Scale::with_range(gtk::Orientation::Horizontal, 0.0, (SPEEDS.len() - 1) as f64, 1.0) {
+                        set_hexpand: true,
+                        set_draw_value: false,
+                        set_value: DEFAULT_SPEED as f64,
+                        connect_value_changed[sender] => move |scale| {
+                            sender.input(LifeGameMsg::SetSpeed(scale.value().round() as usize));
+                        },
+                    },
+                    gtk::Label {
+                        #[watch]
+                        set_label: &speed_name(SPEEDS[model.speed]),
+                    },
+                    gtk::Label {
+                        set_label: "Generations per frame",
+                    },
+                    gtk::SpinButton::with_range(1.0, MAX_GENERATIONS_PER_FRAME as f64, 1.0) {
+                        set_value: 1.0,
+                        set_tooltip_text: Some("The number of generations computed before the board is drawn"),
+                        connect_value_changed[sender] => move |spin| {
+                            sender.input(LifeGameMsg::SetGenerationsPerFrame(spin.value() as usize));
+                        },
+                    },
                 },
                 gtk::Label {
                     #[watch]
                     set_label: &format!("Generation: {}", model.life_game.get_generation()),
                 },
-                #[local_ref]
-                game_grid -> gtk::Grid {
-                    set_orientation: gtk::Orientation::Vertical,
-                    set_column_spacing: 5,
-                    set_row_spacing: 5,
-                }
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Horizontal,
+                    set_spacing: 5,
+                    gtk::CheckButton {
+                        set_label: Some("Stop on cycle"),
+                        connect_toggled[sender] => move |button| {
+                            sender.input(LifeGameMsg::SetStopOnCycle(button.is_active()));
+                        },
+                    },
+                    gtk::Label {
+                        #[watch]
+                        set_label: &match &model.cycle {
+                            Some(cycle) => format!("Cycle: {}", cycle),
+                            None => String::new(),
+                        },
+                    },
+                },
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Horizontal,
+                    set_spacing: 5,
+                    gtk::Button {
+                        set_label: "Step back",
+                        #[watch]
+                        set_sensitive: model.history.can_step_back(),
+                        connect_clicked => LifeGameMsg::StepBack,
+                    },
+                    gtk::Button {
+                        set_label: "Undo",
+                        set_tooltip_text: Some("Ctrl+Z"),
+                        #[watch]
+                        set_sensitive: model.history.can_undo(),
+                        connect_clicked => LifeGameMsg::Undo,
+                    },
+                    gtk::Button {
+                        set_label: "Redo",
+                        set_tooltip_text: Some("Ctrl+Shift+Z"),
+                        #[watch]
+                        set_sensitive: model.history.can_redo(),
+                        connect_clicked => LifeGameMsg::Redo,
+                    },
+                    gtk::Button {
+                        set_label: "Clear",
+                        connect_clicked => LifeGameMsg::Clear,
+                    },
+                },
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Horizontal,
+                    set_spacing: 5,
+                    gtk::Label {
+                        set_label: "Timeline",
+                    },
+                    gtk::Scale {
+                        set_hexpand: true,
+                        set_digits: 0,
+                        #[watch]
+                        set_range: (0.0, model.timeline.len().saturating_sub(1).max(1) as f64),
+                        #[watch]
+                        set_value: model.timeline.find(model.life_game.get_generation()).unwrap_or(0) as f64,
+                        #[watch]
+                        set_sensitive: model.timeline.len() > 1,
+                        connect_change_value[sender] => move |_scale, _scroll, value| {
+                            sender.input(LifeGameMsg::ScrubTimeline(value.round().max(0.0) as usize));
+                            gtk::glib::Propagation::Proceed
+                        },
+                    },
+                    gtk::Label {
+                        set_label: "Keep",
+                    },
+                    gtk::SpinButton::with_range(1.0, 100000.0, 1.0) {
+                        set_value: model.timeline.get_capacity() as f64,
+                        connect_value_changed[sender] => move |spin| {
+                            sender.input(LifeGameMsg::SetTimelineCapacity(spin.value() as usize));
+                        },
+                    },
+                    gtk::Label {
+                        #[watch]
+                        set_label: &format!("{} KiB", model.timeline.get_compressed_size() / 1024),
+                    },
+                },
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Horizontal,
+                    set_spacing: 5,
+                    gtk::Button {
+                        set_label: "Fit pattern",
+                        set_tooltip_text: Some("Fit the pattern to the window (F)"),
+                        connect_clicked => LifeGameMsg::FitPattern,
+                    },
+                    gtk::Button {
+                        set_label: "Center origin",
+                        set_tooltip_text: Some("Center the origin in the window (Home)"),
+                        connect_clicked => LifeGameMsg::CenterOrigin,
+                    },
+                    gtk::Label {
+                        set_label: "Size",
+                    },
+                    gtk::SpinButton::with_range(1.0, MAX_BOARD_SIZE as f64, 1.0) {
+                        set_value: width as f64,
+                        connect_value_changed[sender] => move |spin| {
+                            sender.input(LifeGameMsg::SetResizeWidth(spin.value() as usize));
+                        },
+                    },
+                    gtk::Label {
+                        set_label: "x",
+                    },
+                    gtk::SpinButton::with_range(1.0, MAX_BOARD_SIZE as f64, 1.0) {
+                        set_value: height as f64,
+                        connect_value_changed[sender] => move |spin| {
+                            sender.input(LifeGameMsg::SetResizeHeight(spin.value() as usize));
+                        },
+                    },
+                    gtk::DropDown::from_strings(&Anchor::ALL.map(|anchor| anchor.name())) {
+                        connect_selected_notify[sender] => move |dropdown| {
+                            if let Some(anchor) = Anchor::ALL.get(dropdown.selected() as usize) {
+                                sender.input(LifeGameMsg::SetResizeAnchor(*anchor));
+                            }
+                        },
+                    },
+                    gtk::Button {
+                        set_label: "Resize",
+                        connect_clicked => LifeGameMsg::Resize,
+                    },
+                },
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Horizontal,
+                    set_spacing: 5,
+                    #[local_ref]
+                    board_area -> gtk::DrawingArea {},
+                    gtk::Box {
+                        set_orientation: gtk::Orientation::Vertical,
+                        set_spacing: 5,
+                        set_width_request: 180,
+                        gtk::Button {
+                            set_label: "Census",
+                            connect_clicked => LifeGameMsg::TakeCensus,
+                        },
+                        gtk::ScrolledWindow {
+                            set_vexpand: true,
+                            set_hscrollbar_policy: gtk::PolicyType::Never,
+                            gtk::Label {
+                                set_xalign: 0.0,
+                                set_yalign: 0.0,
+                                set_selectable: true,
+                                #[watch]
+                                set_label: &census_text(model.census.as_ref()),
+                            },
+                        },
+                    },
+                },
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Horizontal,
+                    set_spacing: 5,
+                    #[local_ref]
+                    chart_area -> gtk::DrawingArea {},
+                    gtk::Box {
+                        set_orientation: gtk::Orientation::Vertical,
+                        set_spacing: 5,
+                        set_width_request: 180,
+                        gtk::Label {
+                            set_xalign: 0.0,
+                            set_yalign: 0.0,
+                            set_vexpand: true,
+                            #[watch]
+                            set_label: &statistics_text(model.statistics.get_latest()),
+                        },
+                        gtk::Button {
+                            set_label: "Export CSV",
+                            set_tooltip_text: Some("Save the statistics of the generations as CSV"),
+                            connect_clicked => LifeGameMsg::ExportStatistics,
+                        },
+                    },
+                },
             }
         }
     }
@@ -89,26 +649,93 @@ impl SimpleComponent for ViewModel {
         root: Self::Root,
         sender: ComponentSender<ViewModel>,
     ) -> ComponentParts<Self> {
-        let cells = FactoryVecDeque::<CellModel>::builder()
-            .launch(gtk::Grid::default())
+        let life_game = LifeGame::with_storage(width, height, Storage::BitPacked);
+        let chart = ChartModel::builder().launch(()).detach();
+        let mut statistics = Statistics::default();
+        statistics.record(GenerationStats::of(&life_game.to_bit_grid(), life_game.get_generation()));
+        let board = BoardModel::builder()
+            .launch(life_game.to_bit_grid())
             .forward(sender.input_sender(),
                 |output| match output {
-                CellOutputMsg::StateChanged { column, row, alive } => LifeGameMsg::StateChanged { column, row, alive },
+                BoardOutputMsg::StateChanged { column, row, alive } => LifeGameMsg::StateChanged { column, row, alive },
+                BoardOutputMsg::Stamp { column, row } => LifeGameMsg::Stamp { column, row },
+                BoardOutputMsg::StampCancelled => LifeGameMsg::SetStampMode(false),
             });
         let mut model = ViewModel {
-            life_game: LifeGame::new(width, height),
-            cell_widgets: cells,
+            window: root.clone(),
+            rule_entry: gtk::Entry::new(),
+            life_game,
+            board,
             timer: false,
             timer_handle: None,
+            rule_error: None,
+            engine: Engine::Grid,
+            hash_life: None,
+            sparse_life: None,
+            hash_step: 0,
+            resize_width: width,
+            resize_height: height,
+            resize_anchor: Anchor::default(),
+            file_error: None,
+            save_format: Format::default(),
+            library: PatternLibrary::with_defaults(),
+            pattern_list: gtk::ListBox::new(),
+            pattern_query: String::new(),
+            pattern_matches: Vec::new(),
+            library_error: None,
+            stamp_mode: false,
+            stamp_pattern: None,
+            stamp_transform: Transform::default(),
+            paste_mode: PasteMode::default(),
+            history: History::default(),
+            timeline: Timeline::default(),
+            cycle_detector: CycleDetector::default(),
+            cycle: None,
+            stop_on_cycle: false,
+            census: None,
+            soup: Soup::new(new_seed()),
+            soup_size: 0,
+            seed_entry: gtk::Entry::new(),
+            soup_error: None,
+            speed: DEFAULT_SPEED,
+            generations_per_frame: 1,
+            last_render: Instant::now(),
+            advance_count: 100,
+            run_condition: RunCondition::default(),
+            run_value: 0,
+            run_until: None,
+            run_message: None,
+            extinction_policy: ExtinctionPolicy::default(),
+            peak_population: 0,
+            statistics,
+            recorded: None,
+            chart,
         };
-        for y in 0 .. height {
-            for x in 0 .. width {
-                model.cell_widgets.guard().push_back((x as i32, y as i32, false));
-            }
-        }
+        model.update_pattern_list();
 
-        let game_grid = model.cell_widgets.widget();
+        let board_area = model.board.widget();
+        let chart_area = model.chart.widget();
+        let rule_entry = &model.rule_entry;
+        let pattern_list = &model.pattern_list;
+        let seed_entry = &model.seed_entry;
         let widgets = view_output!();
+
+        // Undo with Ctrl+Z and redo with Ctrl+Shift+Z.
+        let key_controller = gtk::EventControllerKey::new();
+        let sender_clone = sender.clone();
+        key_controller.connect_key_pressed(move |_controller, key, _code, state| {
+            if !state.contains(gtk::gdk::ModifierType::CONTROL_MASK) {
+                return gtk::glib::Propagation::Proceed;
+            }
+            let msg = match key {
+                gtk::gdk::Key::z if !state.contains(gtk::gdk::ModifierType::SHIFT_MASK) => LifeGameMsg::Undo,
+                gtk::gdk::Key::z | gtk::gdk::Key::Z => LifeGameMsg::Redo,
+                _ => return gtk::glib::Propagation::Proceed,
+            };
+            sender_clone.input(msg);
+            gtk::glib::Propagation::Stop
+        });
+        root.add_controller(key_controller);
         ComponentParts { model, widgets }
     }
     fn update(
@@ -120,79 +747,821 @@ impl SimpleComponent for ViewModel {
             LifeGameMsg::StartStop => {
                 if !self.timer {
                     self.timer = true;
+                    self.run_until = None;
+                    self.run_message = None;
+                    self.peak_population = self.life_game.population();
                     if self.timer_handle.is_none() {
-                        let notify = Arc::new(Notify::new());
-                        let notify_clone = notify.clone();
-                        self.timer_handle = Some(notify);
-                        tokio::spawn(async move {
-                            let mut interval = time::interval(Duration::from_secs(1));
-                            loop {
-                                select! {
-                                    _ = interval.tick() => {
-                                        sender.input(LifeGameMsg::NextGeneration);
-                                    }
-                                    _ = notify_clone.notified() => {
-                                        break;
-                                    }
-                                }
-                            }
-                        });
+                        self.start_timer(&sender);
                     }
+                    self.accept_event(false);
                 } else {
-                    self.timer = false;
-                    if let Some(handle) = self.timer_handle.take() {
-                        handle.notify_one();
-                    }
+                    self.end_run(EndReason::Stopped, &sender);
+                    self.update_all_cells();
                 }
-                self.accept_event(!self.timer);
             }
-            LifeGameMsg::NextGeneration => {
-                self.life_game.next_generation();
+            LifeGameMsg::Step => {
+                self.stop_timer();
+                let recorded = self.begin_steps();
+                self.next_generation();
+                self.end_steps(recorded);
                 self.update_all_cells();
             }
-            LifeGameMsg::SelectPattern(pattern) => {
+            LifeGameMsg::Advance => {
+                let target = self.life_game.get_generation().saturating_add(self.advance_count);
+                self.start_run(RunCondition::Generation(target), &sender);
+            }
+            LifeGameMsg::SetAdvanceCount(count) => {
+                self.advance_count = count.clamp(1, MAX_RUN_VALUE);
+            }
+            LifeGameMsg::SetRunCondition(condition) => {
+                self.run_condition = condition;
+            }
+            LifeGameMsg::SetRunValue(value) => {
+                self.run_value = value.min(MAX_RUN_VALUE);
+            }
+            LifeGameMsg::RunUntil => {
+                self.start_run(self.run_condition.with_value(self.run_value), &sender);
+            }
+            LifeGameMsg::Tick => {
+                if !self.timer {
+                    return;
+                }
+                // Run as many generations as fit in a drawing at the fastest speed.
+                let start = Instant::now();
+                let fastest = self.get_delay().is_zero();
+                let mut generations = 0;
+                let mut recorded = self.begin_steps();
+                while self.timer
+                    && (generations < self.generations_per_frame || fastest && start.elapsed() < RENDER_INTERVAL / 2)
+                {
+                    generations += 1;
+                    match self.next_generation() {
+                        Some(EndReason::Extinct) if self.extinction_policy == ExtinctionPolicy::Reseed => {
+                            self.end_steps(recorded);
+                            let generation = self.life_game.get_generation();
+                            let end = RunEnd::new(EndReason::Extinct, generation, self.peak_population);
+                            sender.input(LifeGameMsg::RunEnded(end));
+                            self.reseed();
+                            recorded = self.begin_steps();
+                        }
+                        Some(reason) => self.end_run(reason, &sender),
+                        None => {}
+                    }
+                }
+                self.end_steps(recorded);
+                if !self.timer || self.last_render.elapsed() >= RENDER_INTERVAL {
+                    self.update_all_cells();
+                }
+            }
+            LifeGameMsg::SetSpeed(speed) => {
+                self.speed = speed.min(SPEEDS.len() - 1);
                 if self.timer {
-                    self.timer = false;
                     if let Some(handle) = self.timer_handle.take() {
                         handle.notify_one();
                     }
+                    self.start_timer(&sender);
                 }
-                self.life_game.set_initialize_pattern(pattern);
-                self.update_all_cells();
+            }
+            LifeGameMsg::SetExtinctionPolicy(policy) => {
+                self.extinction_policy = policy;
+            }
+            LifeGameMsg::RunEnded(end) => {
+                self.run_message = Some(end.to_string());
+            }
+            LifeGameMsg::SetGenerationsPerFrame(generations) => {
+                self.generations_per_frame = generations.clamp(1, MAX_GENERATIONS_PER_FRAME);
+            }
+            LifeGameMsg::StepBack => {
+                self.stop_timer();
+                if self.history.step_back(&mut self.life_game) {
+                    self.restore_board();
+                }
+            }
+            LifeGameMsg::Undo => {
+                self.stop_timer();
+                if self.history.undo(&mut self.life_game) {
+                    self.restore_board();
+                }
+            }
+            LifeGameMsg::Redo => {
+                self.stop_timer();
+                if self.history.redo(&mut self.life_game) {
+                    self.restore_board();
+                }
+            }
+            LifeGameMsg::SetStopOnCycle(stop_on_cycle) => {
+                self.stop_on_cycle = stop_on_cycle;
+            }
+            LifeGameMsg::TakeCensus => {
+                self.take_census();
+            }
+            LifeGameMsg::ExportStatistics => {
+                let dialog = gtk::FileDialog::builder()
+                    .title("Export statistics")
+                    .modal(true)
+                    .initial_name("statistics.csv")
+                    .build();
+                dialog.save(Some(&self.window), gtk::gio::Cancellable::NONE, move |result| {
+                    if let Ok(file) = result
+                        && let Some(path) = file.path()
+                    {
+                        sender.input(LifeGameMsg::ExportStatisticsToFile(path));
+                    }
+                });
+            }
+            LifeGameMsg::ExportStatisticsToFile(path) => {
+                self.file_error = std::fs::write(&path, self.statistics.to_csv())
+                    .err()
+                    .map(|error| format!("{}: {}", path.display(), error));
+            }
+            LifeGameMsg::SetSoupDensity(density) => {
+                self.soup.set_density(density);
+            }
+            LifeGameMsg::SetSoupSymmetry(symmetry) => {
+                self.soup.set_symmetry(symmetry);
+            }
+            LifeGameMsg::SetSoupSize(size) => {
+                self.soup_size = size;
+            }
+            LifeGameMsg::NewSoup => {
+                self.seed_entry.set_text(&new_seed().to_string());
+                sender.input(LifeGameMsg::FillSoup);
+            }
+            LifeGameMsg::FillSoup => {
+                let seed = match self.seed_entry.text().trim().parse::<u64>() {
+                    Ok(seed) => seed,
+                    Err(_) => {
+                        self.soup_error = Some("the seed must be a number".to_string());
+                        return;
+                    }
+                };
+                self.soup_error = None;
+                let mut soup = Soup::new(seed);
+                soup.set_density(self.soup.get_density());
+                soup.set_symmetry(self.soup.get_symmetry());
+                self.soup = soup;
+                self.fill_soup();
+            }
+            LifeGameMsg::ScrubTimeline(index) => {
+                self.stop_timer();
+                if let Some(snapshot) = self.timeline.get(index) {
+                    self.life_game.restore(snapshot);
+                    self.restore_board();
+                }
+            }
+            LifeGameMsg::SetTimelineCapacity(capacity) => {
+                self.timeline.set_capacity(capacity);
+            }
+            LifeGameMsg::Clear => {
+                self.stop_timer();
+                let before = self.life_game.snapshot();
+                self.life_game.reset();
+                self.history.record_replace(before, &self.life_game);
+                self.restore_board();
+            }
+            LifeGameMsg::SelectPattern(row) => {
+                if let Some(&index) = self.pattern_matches.get(row) {
+                    let pattern = self.library.get_patterns()[index].get_pattern().clone();
+                    if self.stamp_mode {
+                        self.stamp_pattern = Some(pattern);
+                        self.update_stamp();
+                    } else if let Err(error) = self.load_pattern(&pattern) {
+                        self.library_error = Some(error.to_string());
+                    }
+                }
+            }
+            LifeGameMsg::SearchPatterns(query) => {
+                self.pattern_query = query;
+                self.update_pattern_list();
+            }
+            LifeGameMsg::OpenLibrary => {
+                let dialog = gtk::FileDialog::builder()
+                    .title("Open pattern library")
+                    .modal(true)
+                    .build();
+                dialog.select_folder(Some(&self.window), gtk::gio::Cancellable::NONE, move |result| {
+                    if let Ok(folder) = result
+                        && let Some(path) = folder.path()
+                    {
+                        sender.input(LifeGameMsg::LoadLibrary(path));
+                    }
+                });
+            }
+            LifeGameMsg::SetStampMode(stamp_mode) => {
+                self.stamp_mode = stamp_mode;
+                // Stamp the pattern selected in the browser, if any.
+                if stamp_mode
+                    && let Some(row) = self.pattern_list.selected_row()
+                    && let Some(&index) = self.pattern_matches.get(row.index() as usize)
+                {
+                    self.stamp_pattern = Some(self.library.get_patterns()[index].get_pattern().clone());
+                }
+                self.update_stamp();
+            }
+            LifeGameMsg::SetStampTransform(transform) => {
+                self.stamp_transform = transform;
+                self.update_stamp();
+            }
+            LifeGameMsg::SetPasteMode(mode) => {
+                self.paste_mode = mode;
+            }
+            LifeGameMsg::Stamp { column, row } => {
+                let Some(pattern) = &self.stamp_pattern else {
+                    return;
+                };
+                let toggled = self.life_game.place_pattern(
+                    pattern.get_cells(),
+                    column,
+                    row,
+                    self.stamp_transform,
+                    self.paste_mode,
+                );
+                self.toggle_unbounded(&toggled);
+                self.history.record(Command::Toggle(toggled));
+                self.reset_cycle();
+                self.board.emit(BoardMsg::Update(self.life_game.to_bit_grid()));
+            }
+            LifeGameMsg::LoadLibrary(path) => {
+                self.library_error = match self.library.load_dir(&path) {
+                    Ok((_, errors)) if errors.is_empty() => None,
+                    Ok((loaded, errors)) => Some(format!(
+                        "Loaded {} patterns, skipped {} files:\n{}",
+                        loaded,
+                        errors.len(),
+                        errors.iter().map(|error| error.to_string()).collect::<Vec<_>>().join("\n")
+                    )),
+                    Err(error) => Some(error.to_string()),
+                };
+                self.update_pattern_list();
+            }
+            LifeGameMsg::SetRule(text) => {
+                match text.parse::<Rule>() {
+                    Ok(rule) => {
+                        self.life_game.set_rule(rule);
+                        self.rule_error = None;
+                        self.reset_cycle();
+                        let hash_result = self.hash_life.as_mut().map(|hash_life| hash_life.set_rule(rule));
+                        let sparse_result = self.sparse_life.as_mut().map(|sparse_life| sparse_life.set_rule(rule));
+                        if let Some(Err(error)) = hash_result.or(sparse_result) {
+                            self.rule_error = Some(error.to_string());
+                        }
+                    }
+                    Err(error) => {
+                        self.rule_error = Some(error.to_string());
+                    }
+                }
+            }
+            LifeGameMsg::SetTopology(topology) => {
+                self.life_game.set_topology(topology);
+                self.hash_life = None;
+                self.sparse_life = None;
+                self.reset_cycle();
+            }
+            LifeGameMsg::SetEngine(engine) => {
+                self.engine = engine;
+                self.hash_life = None;
+                self.sparse_life = None;
+            }
+            LifeGameMsg::SetHashStep(step) => {
+                self.hash_step = step;
+            }
+            LifeGameMsg::FitPattern => {
+                self.board.emit(BoardMsg::FitPattern);
+            }
+            LifeGameMsg::CenterOrigin => {
+                self.board.emit(BoardMsg::CenterOrigin);
+            }
+            LifeGameMsg::SetResizeWidth(width) => {
+                self.resize_width = width;
+            }
+            LifeGameMsg::SetResizeHeight(height) => {
+                self.resize_height = height;
+            }
+            LifeGameMsg::SetResizeAnchor(anchor) => {
+                self.resize_anchor = anchor;
+            }
+            LifeGameMsg::Resize => {
+                let before = self.life_game.snapshot();
+                self.life_game.resize(self.resize_width, self.resize_height, self.resize_anchor);
+                self.history.record_replace(before, &self.life_game);
+                self.reset_cycle();
+                self.hash_life = None;
+                self.sparse_life = None;
+                self.board.emit(BoardMsg::Update(self.life_game.to_bit_grid()));
+            }
+            LifeGameMsg::OpenFile => {
+                let dialog = gtk::FileDialog::builder()
+                    .title("Open pattern")
+                    .modal(true)
+                    .default_filter(&pattern_filter())
+                    .build();
+                dialog.open(Some(&self.window), gtk::gio::Cancellable::NONE, move |result| {
+                    if let Ok(file) = result
+                        && let Some(path) = file.path()
+                    {
+                        sender.input(LifeGameMsg::LoadFile(path));
+                    }
+                });
+            }
+            LifeGameMsg::LoadFile(path) => {
+                self.file_error = self.load_file(&path).err().map(|error| format!("{}: {}", path.display(), error));
+            }
+            LifeGameMsg::SetSaveFormat(format) => {
+                self.save_format = format;
+            }
+            LifeGameMsg::SaveFile => {
+                let dialog = gtk::FileDialog::builder()
+                    .title("Save pattern")
+                    .modal(true)
+                    .initial_name(format!("pattern.{}", self.save_format.extension()))
+                    .default_filter(&pattern_filter())
+                    .build();
+                dialog.save(Some(&self.window), gtk::gio::Cancellable::NONE, move |result| {
+                    if let Ok(file) = result
+                        && let Some(path) = file.path()
+                    {
+                        sender.input(LifeGameMsg::SaveToFile(path));
+                    }
+                });
+            }
+            LifeGameMsg::SaveToFile(path) => {
+                let text = match (self.save_format, &self.hash_life, &self.sparse_life) {
+                    (Format::Macrocell, Some(hash_life), _) if self.get_engine() == Engine::HashLife => {
+                        format::write_macrocell(hash_life)
+                    }
+                    (format, _, Some(sparse_life)) if self.get_engine() == Engine::Sparse => {
+                        format.write(&PatternData::from_universe(sparse_life))
+                    }
+                    (format, _, _) => format.write(&PatternData::from_universe(&self.life_game)),
+                };
+                self.file_error = std::fs::write(&path, text)
+                    .err()
+                    .map(|error| format!("{}: {}", path.display(), error));
             }
             LifeGameMsg::StateChanged { column, row, alive } => {
-                let cell = self.life_game.get_cell_mut(column, row).unwrap();
-                cell.set_alive(alive);
+                self.life_game.set_alive(column, row, alive);
+                if let Some(hash_life) = &mut self.hash_life {
+                    hash_life.set_alive(column, row, alive);
+                }
+                if let Some(sparse_life) = &mut self.sparse_life {
+                    sparse_life.set_alive(column, row, alive);
+                }
+                self.history.record(Command::Toggle(vec![(column, row)]));
+                self.reset_cycle();
             }
         }
     }
 }
 
-impl ViewModel {
-    fn update_all_cells(&mut self) {
-        for y in 0..self.life_game.get_height() {
-            for x in 0..self.life_game.get_width() {
-                let cell = self.life_game.get_cell(x as i32, y as i32).unwrap();
-                let index = self.life_game.get_index(x as i32, y as i32);
-                //self.cell_widgets.guard().get_mut(index).unwrap().set_alive(cell.is_alive());
-                self.cell_widgets.guard().send(index, CellMsg::NextGeneration(cell.is_alive()));
+/// Get the name of the delay between the frames.
+fn speed_name(delay: Duration) -> String {
+    if delay.is_zero() {
+        "Fastest".to_string()
+    } else if delay < Duration::from_secs(1) {
+        format!("{} ms", delay.as_millis())
+    } else {
+        format!("{} s", delay.as_secs())
+    }
+}
+
+/// Get a new seed of the random soup from the current time.
+fn new_seed() -> u64 {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_nanos() as u64);
+    Random::new(nanos).next_u64()
+}
+
+/// Get the note shown when the engine falls back to the grid engine for the topology.
+fn engine_text(engine: Engine, topology: Topology) -> String {
+    if engine.supports(topology) {
+        return String::new();
+    }
+    format!("{} runs on the plane only, the grid engine is used on the {}", engine.name(), topology.name().to_lowercase())
+}
+
+/// Get the text of the statistics of the latest generation.
+fn statistics_text(stats: Option<&GenerationStats>) -> String {
+    let Some(stats) = stats else {
+        return String::new();
+    };
+    let bounds = stats.get_bounding_box().map_or("-".to_string(), |bounds| {
+        let (left, top) = bounds.get_min();
+        format!("{} x {} at ({}, {})", bounds.get_width(), bounds.get_height(), left, top)
+    });
+    let span = match stats.get_span() {
+        0 | 1 => String::new(),
+        span => format!(" over {} generations", span),
+    };
+    format!(
+        "Population: {}\nBirths: {}{}\nDeaths: {}{}\nBounding box: {}\nDensity: {:.2}%\nRate of change: {:+.2}",
+        stats.get_population(),
+        stats.get_births(),
+        span,
+        stats.get_deaths(),
+        span,
+        bounds,
+        stats.get_density() * 100.0,
+        stats.get_rate_of_change(),
+    )
+}
+
+/// Get the text of the census panel.
+fn census_text(census: Option<&Census>) -> String {
+    let Some(census) = census else {
+        return String::new();
+    };
+    if census.get_entries().is_empty() {
+        return "No objects".to_string();
+    }
+    let mut text = String::new();
+    for entry in census.get_entries() {
+        text.push_str(&format!("{} x {}\n", entry.get_count(), entry.get_name()));
+        for (x, y) in entry.get_locations() {
+            text.push_str(&format!("    ({}, {})\n", x, y));
+        }
+    }
+    text
+}
+
+/// Check if the cells of the universe fit on the largest board.
+fn fits_board<U: Universe>(universe: &U) -> bool {
+    universe.bounding_box().is_none_or(|bounds| {
+        bounds.get_width() <= MAX_BOARD_SIZE as u64 && bounds.get_height() <= MAX_BOARD_SIZE as u64
+    })
+}
+
+/// Copy the cells of the universe on the board and its generation to the grid.
+fn copy_universe<U: Universe>(universe: &U, life_game: &mut LifeGame) {
+    life_game.reset();
+    for y in 0..life_game.get_height() as i32 {
+        for x in 0..life_game.get_width() as i32 {
+            if universe.is_alive(x, y) {
+                life_game.set_alive(x, y, true);
             }
         }
-        if !self.life_game.keep_alive() {
+    }
+    life_game.set_generation(universe.get_generation());
+}
+
+/// Filter of the pattern files in the file dialogs.
+fn pattern_filter() -> gtk::FileFilter {
+    let filter = gtk::FileFilter::new();
+    filter.set_name(Some("Pattern files"));
+    for pattern in ["*.rle", "*.cells", "*.lif", "*.life", "*.mc"] {
+        filter.add_pattern(pattern);
+    }
+    filter
+}
+
+impl ViewModel {
+    fn stop_timer(&mut self) {
+        if self.timer {
             self.timer = false;
             if let Some(handle) = self.timer_handle.take() {
                 handle.notify_one();
             }
-            self.accept_event(!self.timer);
-            self.life_game.reset_generation();
+            self.accept_event(true);
+        }
+        if self.run_until.take().is_some() {
+            self.run_message = Some(format!("Stopped at generation {}", self.life_game.get_generation()));
         }
     }
-    fn accept_event(&mut self, accept: bool) {
-        for y in 0..self.life_game.get_height() {
-            for x in 0..self.life_game.get_width() {
-                let index = self.life_game.get_index(x as i32, y as i32);
-                self.cell_widgets.guard().send(index, CellMsg::AcceptClick(accept));
+    /// Stop the run and emit the event of its end.
+    fn end_run(&mut self, reason: EndReason, sender: &ComponentSender<Self>) {
+        self.run_until = None;
+        self.stop_timer();
+        let end = RunEnd::new(reason, self.life_game.get_generation(), self.peak_population);
+        sender.input(LifeGameMsg::RunEnded(end));
+    }
+    /// Get the delay between the ticks. The runs until a condition are as fast as possible.
+    fn get_delay(&self) -> Duration {
+        match self.run_until {
+            Some(_) => Duration::ZERO,
+            None => SPEEDS[self.speed],
+        }
+    }
+    /// Run as fast as possible until the condition is met.
+    fn start_run(&mut self, condition: RunCondition, sender: &ComponentSender<Self>) {
+        self.stop_timer();
+        let mut run_until = RunUntil::new(condition);
+        if run_until.check(&self.life_game) {
+            self.run_message = Some(format!("Already reached {}", condition));
+            return;
+        }
+        self.run_until = Some(run_until);
+        self.run_message = Some(format!("Running until {}", condition));
+        self.peak_population = self.life_game.population();
+        self.timer = true;
+        self.start_timer(sender);
+        self.accept_event(false);
+    }
+    /// Start the task which sends the ticks at the current speed.
+    fn start_timer(&mut self, sender: &ComponentSender<Self>) {
+        let notify = Arc::new(Notify::new());
+        let notify_clone = notify.clone();
+        self.timer_handle = Some(notify);
+        // The fastest speed ticks at the drawing rate, and each tick runs as many generations as it can.
+        let delay = match self.get_delay() {
+            Duration::ZERO => RENDER_INTERVAL,
+            delay => delay,
+        };
+        let sender = sender.clone();
+        tokio::spawn(async move {
+            let mut interval = time::interval(delay);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+            loop {
+                select! {
+                    _ = interval.tick() => {
+                        sender.input(LifeGameMsg::Tick);
+                    }
+                    _ = notify_clone.notified() => {
+                        break;
+                    }
+                }
             }
+        });
+    }
+    /// Record the board before the steps of a frame in the timeline and the history.
+    /// The generation recorded at the end of the last frame is reused if the board was not changed since.
+    fn begin_steps(&mut self) -> (BitGrid, u64) {
+        let generation = self.life_game.get_generation();
+        let (cells, snapshot) = match self.recorded.take() {
+            Some((cells, snapshot)) if snapshot.get_generation() == generation => (cells, snapshot),
+            _ => {
+                let cells = self.life_game.to_bit_grid();
+                let snapshot = Snapshot::new(&cells, generation);
+                self.timeline.record(snapshot.clone());
+                (cells, snapshot)
+            }
+        };
+        self.history.record_step(snapshot);
+        (cells, generation)
+    }
+    /// Record the board after the steps of a frame in the timeline and the statistics,
+    /// so the bookkeeping is done once per frame rather than once per generation.
+    fn end_steps(&mut self, (before_cells, before_generation): (BitGrid, u64)) {
+        let generation = self.life_game.get_generation();
+        if generation == before_generation {
+            return;
+        }
+        let cells = self.life_game.to_bit_grid();
+        let snapshot = Snapshot::new(&cells, generation);
+        self.timeline.record(snapshot.clone());
+        self.statistics.record(GenerationStats::new(&before_cells, before_generation, &cells, generation));
+        self.recorded = Some((cells, snapshot));
+    }
+    /// Advance a generation without drawing the board or recording it.
+    /// Returns the reason if the run should end.
+    fn next_generation(&mut self) -> Option<EndReason> {
+        match self.get_engine() {
+            Engine::Grid => self.life_game.next_generation(),
+            Engine::Sparse => self.step_sparse_life(),
+            Engine::HashLife => self.step_hash_life(),
         }
+        self.peak_population = self.peak_population.max(self.life_game.population());
+        // The detector is idle once the cycle is found, until the board is changed.
+        if self.cycle.is_none()
+            && let Some(cycle) = self.cycle_detector.observe(&self.life_game)
+        {
+            // Count the objects once the board settled.
+            self.cycle = Some(cycle);
+            self.take_census();
+            if self.stop_on_cycle {
+                return Some(EndReason::Cycle(cycle));
+            }
+        }
+        if let Some(run_until) = &mut self.run_until
+            && run_until.check(&self.life_game)
+        {
+            return Some(EndReason::Condition(run_until.get_condition()));
+        }
+        if self.timer && !self.keep_alive() && self.extinction_policy != ExtinctionPolicy::KeepRunning {
+            return Some(EndReason::Extinct);
+        }
+        None
+    }
+    /// Load the pattern into the board and show the rule of the pattern.
+    /// The board is left as it is if the pattern is too large.
+    fn load_pattern(&mut self, pattern: &PatternData) -> Result<(), FormatError> {
+        let before = self.life_game.snapshot();
+        pattern.load_into(&mut self.life_game)?;
+        self.stop_timer();
+        self.history.record_replace(before, &self.life_game);
+        self.reset_cycle();
+        self.rule_entry.set_text(&self.life_game.get_rule().to_string());
+        self.rule_error = None;
+        self.hash_life = None;
+        self.sparse_life = None;
+        self.board.emit(BoardMsg::Update(self.life_game.to_bit_grid()));
+        self.board.emit(BoardMsg::FitPattern);
+        Ok(())
+    }
+    /// Read the pattern file and load it.
+    /// A macrocell pattern larger than the board is kept as it is by the HashLife engine.
+    fn load_file(&mut self, path: &Path) -> Result<(), String> {
+        let text = std::fs::read_to_string(path).map_err(|error| error.to_string())?;
+        let format = Format::detect(&text);
+        if format != Format::Macrocell {
+            let pattern = format.parse(&text).map_err(|error| error.to_string())?;
+            return self.load_pattern(&pattern).map_err(|error| error.to_string());
+        }
+        let hash_life = format::parse_macrocell(&text).map_err(|error| error.to_string())?;
+        if fits_board(&hash_life) {
+            self.load_pattern(&PatternData::from_universe(&hash_life)).map_err(|error| error.to_string())?;
+        } else if self.get_engine() == Engine::HashLife {
+            self.load_hash_life(hash_life);
+        } else if let Some(bounds) = hash_life.bounding_box() {
+            return Err(format!(
+                "the pattern of {} x {} cells is too large for the board, use the HashLife engine on the plane",
+                bounds.get_width(),
+                bounds.get_height()
+            ));
+        }
+        Ok(())
+    }
+    /// Load the universe into the HashLife engine, showing the cells which are on the board.
+    /// The cells are kept at their positions, so the board shows the cells right and below the origin.
+    fn load_hash_life(&mut self, hash_life: HashLife) {
+        self.stop_timer();
+        let before = self.life_game.snapshot();
+        self.life_game.set_rule(*hash_life.get_rule());
+        self.hash_life = Some(hash_life);
+        self.sparse_life = None;
+        self.copy_hash_life();
+        self.history.record_replace(before, &self.life_game);
+        self.reset_cycle();
+        self.rule_entry.set_text(&self.life_game.get_rule().to_string());
+        self.rule_error = None;
+        self.board.emit(BoardMsg::Update(self.life_game.to_bit_grid()));
+        self.board.emit(BoardMsg::FitPattern);
+    }
+    /// Fill the board, or the centered square of the soup size, with the soup.
+    fn fill_soup(&mut self) {
+        self.stop_timer();
+        self.apply_soup();
+        self.board.emit(BoardMsg::Update(self.life_game.to_bit_grid()));
+    }
+    /// Fill the board with a soup of a new seed, and start the run over from generation 0.
+    fn reseed(&mut self) {
+        let mut soup = Soup::new(new_seed());
+        soup.set_density(self.soup.get_density());
+        soup.set_symmetry(self.soup.get_symmetry());
+        self.soup = soup;
+        self.seed_entry.set_text(&self.soup.get_seed().to_string());
+        self.life_game.set_generation(0);
+        self.apply_soup();
+        self.hash_life = None;
+        self.sparse_life = None;
+        self.peak_population = self.life_game.population();
+    }
+    /// Fill the board with the soup without stopping the run.
+    fn apply_soup(&mut self) {
+        let area = (self.soup_size > 0).then(|| {
+            let (width, height) = (self.life_game.get_width() as i32, self.life_game.get_height() as i32);
+            let size = self.soup_size as i32;
+            let (left, top) = ((width - size) / 2, (height - size) / 2);
+            BoundingBox::new(left, top, left + size - 1, top + size - 1)
+        });
+        let toggled = self.life_game.fill_soup(&self.soup, area);
+        self.toggle_unbounded(&toggled);
+        self.history.record(Command::Toggle(toggled));
+        self.reset_cycle();
+    }
+    /// Take the census, grouping the objects over the period of the cycle if it is found.
+    fn take_census(&mut self) {
+        let phases = self
+            .cycle
+            .map_or(CENSUS_PHASES, |cycle| cycle.get_period().min(MAX_CENSUS_PHASES as u64) as u32);
+        self.census = Some(Census::take(&self.life_game, phases));
+    }
+    /// Forget the cycle found so far and the recorded generation, since the board was changed,
+    /// and record the changed board in the statistics.
+    fn reset_cycle(&mut self) {
+        self.cycle_detector.reset();
+        self.cycle = None;
+        self.recorded = None;
+        let stats = GenerationStats::of(&self.life_game.to_bit_grid(), self.life_game.get_generation());
+        self.statistics.record(stats);
+        self.update_chart();
+    }
+    /// Show the population of the latest generations in the chart.
+    fn update_chart(&self) {
+        let skip = self.statistics.len().saturating_sub(CHART_LENGTH);
+        let points = self
+            .statistics
+            .get_series()
+            .skip(skip)
+            .map(|stats| (stats.get_generation(), stats.get_population()))
+            .collect();
+        self.chart.emit(ChartMsg::Update(points));
+    }
+    /// Show the board restored by the history. The unbounded engines start over from the board.
+    fn restore_board(&mut self) {
+        self.reset_cycle();
+        self.hash_life = None;
+        self.sparse_life = None;
+        self.board.emit(BoardMsg::Update(self.life_game.to_bit_grid()));
+    }
+    /// Show the transformed stamp pattern on the board, if the stamp tool is active.
+    fn update_stamp(&self) {
+        let stamp = match (self.stamp_mode, &self.stamp_pattern) {
+            (true, Some(pattern)) => Some(self.stamp_transform.apply_to_cells(pattern.get_cells())),
+            _ => None,
+        };
+        self.board.emit(BoardMsg::SetStamp(stamp));
+    }
+    /// Fill the pattern browser with the library patterns matching the search query.
+    fn update_pattern_list(&mut self) {
+        self.pattern_list.remove_all();
+        self.pattern_matches = self.library.search(&self.pattern_query);
+        for &index in &self.pattern_matches {
+            let pattern = &self.library.get_patterns()[index];
+            let text = match pattern.get_period() {
+                Some(period) => format!("{} ({}, p{})", pattern.get_name(), pattern.get_category(), period),
+                None => format!("{} ({})", pattern.get_name(), pattern.get_category()),
+            };
+            let label = gtk::Label::new(Some(&text));
+            label.set_xalign(0.0);
+            let rule = pattern.get_rule().copied().unwrap_or_default();
+            label.set_tooltip_text(Some(&format!("{}\nRule: {}", pattern.get_description(), rule)));
+            self.pattern_list.append(&label);
+        }
+    }
+    /// Step the HashLife engine and copy the cells on the board back to the grid.
+    /// The HashLife universe is unbounded, so cells which leave the board are kept there.
+    fn step_hash_life(&mut self) {
+        let life_game = &mut self.life_game;
+        let hash_life = self.hash_life.get_or_insert_with(|| {
+            let mut hash_life = HashLife::from_cells(life_game.live_cells());
+            hash_life.set_generation(life_game.get_generation());
+            hash_life
+        });
+        if let Err(error) = hash_life.set_rule(*life_game.get_rule()) {
+            self.rule_error = Some(error.to_string());
+            self.hash_life = None;
+            life_game.next_generation();
+            return;
+        }
+        hash_life.step_pow2(self.hash_step);
+        self.copy_hash_life();
+    }
+    /// Copy the cells of the HashLife engine on the board and its generation to the grid.
+    fn copy_hash_life(&mut self) {
+        if let Some(hash_life) = &self.hash_life {
+            copy_universe(hash_life, &mut self.life_game);
+        }
+    }
+    /// Step the sparse engine and copy the cells on the board back to the grid.
+    /// The sparse universe is unbounded, so cells which leave the board are kept there.
+    fn step_sparse_life(&mut self) {
+        let life_game = &mut self.life_game;
+        let sparse_life = self.sparse_life.get_or_insert_with(|| {
+            let mut sparse_life = SparseLife::from_cells(life_game.live_cells());
+            sparse_life.set_generation(life_game.get_generation());
+            sparse_life
+        });
+        if let Err(error) = sparse_life.set_rule(*life_game.get_rule()) {
+            self.rule_error = Some(error.to_string());
+            self.sparse_life = None;
+            life_game.next_generation();
+            return;
+        }
+        sparse_life.next_generation();
+        copy_universe(sparse_life, life_game);
+    }
+    /// Toggle the cells in the unbounded universes, as they were toggled on the board.
+    fn toggle_unbounded(&mut self, toggled: &[(i32, i32)]) {
+        if let Some(hash_life) = &mut self.hash_life {
+            for &(x, y) in toggled {
+                hash_life.set_alive(x, y, !hash_life.is_alive(x, y));
+            }
+        }
+        if let Some(sparse_life) = &mut self.sparse_life {
+            for &(x, y) in toggled {
+                sparse_life.set_alive(x, y, !sparse_life.is_alive(x, y));
+            }
+        }
+    }
+    /// Get the engine which computes the generations.
+    /// The grid engine is used instead of the selected one if it does not support the topology.
+    fn get_engine(&self) -> Engine {
+        if self.engine.supports(self.life_game.get_topology()) { self.engine } else { Engine::Grid }
+    }
+    /// Check if the engine kept any cell alive.
+    fn keep_alive(&self) -> bool {
+        match (self.get_engine(), &self.hash_life, &self.sparse_life) {
+            (Engine::HashLife, Some(hash_life), _) => hash_life.keep_alive(),
+            (Engine::Sparse, _, Some(sparse_life)) => sparse_life.keep_alive(),
+            _ => self.life_game.keep_alive(),
+        }
+    }
+    fn update_all_cells(&mut self) {
+        self.last_render = Instant::now();
+        self.board.emit(BoardMsg::Update(self.life_game.to_bit_grid()));
+        self.update_chart();
+    }
+    fn accept_event(&mut self, accept: bool) {
+        self.board.emit(BoardMsg::AcceptClick(accept));
     }
 }
\ No newline at end of file